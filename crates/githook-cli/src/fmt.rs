@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use colored::*;
+use githook::{parse_spanned, tokenize_with_spans, Diagnostic, SpannedToken, Token};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Format the given hook files in place, or only report unformatted ones with `check`.
+/// Returns whether every file was already formatted.
+pub fn run(files: Vec<PathBuf>, check: bool) -> Result<bool> {
+    let files = if files.is_empty() { default_files()? } else { files };
+
+    if files.is_empty() {
+        println!("{}", "No .ghook files found in .githook/".dimmed());
+        return Ok(true);
+    }
+
+    let mut all_formatted = true;
+
+    for path in &files {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+
+        let Some(formatted) = format_source(&source, path)? else {
+            continue;
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            all_formatted = false;
+            println!("{} {}", "Diff in".red(), path.display());
+            print_diff(&source, &formatted);
+        } else {
+            fs::write(path, &formatted)
+                .with_context(|| format!("Failed to write {:?}", path))?;
+            println!("{} Formatted {}", "✓".green(), path.display());
+        }
+    }
+
+    Ok(all_formatted)
+}
+
+/// All `.ghook` files directly inside `.githook/`, sorted by name
+fn default_files() -> Result<Vec<PathBuf>> {
+    let dir = Path::new(".githook");
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .context("Failed to read .githook directory")?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ghook"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the canonical source, or `None` if the file has to be left alone
+fn format_source(source: &str, path: &Path) -> Result<Option<String>> {
+    let tokens = match tokenize_with_spans(source) {
+        Ok(tokens) => tokens,
+        Err(lex_error) => {
+            eprintln!("{}", Diagnostic::new_lex(source, lex_error).with_color(crate::use_color()));
+            anyhow::bail!("Failed to format {}", path.display());
+        }
+    };
+
+    let comments = count_comments(&tokens);
+
+    let ast = match parse_spanned(tokens) {
+        Ok(ast) => ast,
+        Err(parse_error) => {
+            eprintln!("{}", Diagnostic::new_parse(source, parse_error).with_color(crate::use_color()));
+            anyhow::bail!("Failed to format {}", path.display());
+        }
+    };
+
+    let formatted = githook::format(&ast);
+
+    // Comments inside conditions or between match arms are not kept in the syntax tree
+    let kept = tokenize_with_spans(&formatted).map(|tokens| count_comments(&tokens)).unwrap_or(0);
+    if kept < comments {
+        eprintln!(
+            "{} Skipping {}: formatting would drop comments that are not on their own line or after a statement",
+            "!".yellow(),
+            path.display()
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(formatted))
+}
+
+fn count_comments(tokens: &[SpannedToken]) -> usize {
+    tokens.iter().filter(|t| matches!(t.token, Token::Comment(_))).count()
+}
+
+/// Print a line diff between the current and the formatted source
+fn print_diff(old: &str, new: &str) {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, lcs[i][j] covers old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new_lines.len() + 1]; old_lines.len() + 1];
+    for i in (0..old_lines.len()).rev() {
+        for j in (0..new_lines.len()).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+            i += 1;
+            j += 1;
+        } else if i < old_lines.len() && (j == new_lines.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            println!("{}", format!("-{}", old_lines[i]).red());
+            i += 1;
+        } else {
+            println!("{}", format!("+{}", new_lines[j]).green());
+            j += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNFORMATTED: &str = "group  lint { run \"cargo fmt\" }\n";
+    const FORMATTED: &str = "group lint {\n  run \"cargo fmt\"\n}\n";
+
+    fn write_hook(dir: &Path, source: &str) -> PathBuf {
+        let path = dir.join("pre-commit.ghook");
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn check_fails_on_unformatted_file_without_touching_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_hook(dir.path(), UNFORMATTED);
+
+        assert!(!run(vec![path.clone()], true).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), UNFORMATTED);
+    }
+
+    #[test]
+    fn check_passes_on_formatted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_hook(dir.path(), FORMATTED);
+
+        assert!(run(vec![path], true).unwrap());
+    }
+
+    #[test]
+    fn formatting_rewrites_the_file_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_hook(dir.path(), UNFORMATTED);
+
+        assert!(run(vec![path.clone()], false).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), FORMATTED);
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use githook::githook_git;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Parse `90`, `30s`, `5m`, `2h` or `1d` into a duration
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (digits, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => input.split_at(pos),
+        None => (input, "s"),
+    };
+
+    let value: u64 = digits
+        .parse()
+        .with_context(|| format!("Invalid duration '{}', expected e.g. 30s, 5m, 2h or 1d", input))?;
+
+    let unit_secs: u64 = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => anyhow::bail!("Invalid duration unit in '{}', expected s, m, h or d", input),
+    };
+
+    let Some(secs) = value.checked_mul(unit_secs) else {
+        anyhow::bail!("Duration '{}' is too large", input);
+    };
+
+    Ok(Duration::from_secs(secs))
+}
+
+/// Whether `hook` last passed less than `interval` ago
+pub fn ran_within(hook: &str, interval: Duration) -> Result<bool> {
+    let Ok(contents) = fs::read_to_string(stamp_path(hook)?) else {
+        return Ok(false);
+    };
+    let Ok(last) = contents.trim().parse::<u64>() else {
+        return Ok(false);
+    };
+
+    Ok(is_recent(last, now_secs(), interval))
+}
+
+/// Whether a run at `last` is less than `interval` before `now`, both in unix seconds
+fn is_recent(last: u64, now: u64, interval: Duration) -> bool {
+    now.saturating_sub(last) < interval.as_secs()
+}
+
+/// Remember that `hook` passed just now
+pub fn record(hook: &str) -> Result<()> {
+    let path = stamp_path(hook)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    fs::write(&path, now_secs().to_string()).with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// `.git/githook/<hook>.last-run`, with path separators in file hooks flattened
+fn stamp_path(hook: &str) -> Result<PathBuf> {
+    let git_dir = githook_git::git_capture(&["rev-parse", "--git-dir"])?;
+    let name: String = hook
+        .chars()
+        .map(|c| if c == '/' || c == '\\' { '_' } else { c })
+        .collect();
+    Ok(PathBuf::from(git_dir).join("githook").join(format!("{}.last-run", name)))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+    }
+
+    #[test]
+    fn rejects_invalid_and_overflowing_durations() {
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn recent_run_is_skipped_and_old_run_is_not() {
+        let interval = Duration::from_secs(300);
+        assert!(is_recent(1_000, 1_100, interval));
+        assert!(!is_recent(1_000, 1_300, interval));
+        assert!(!is_recent(1_000, 5_000, interval));
+    }
+}
@@ -1,13 +1,19 @@
+mod fmt;
+mod last_run;
 mod updater;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::*;
 use githook::prelude::*;
-use githook::{parse_spanned, tokenize_with_spans, Diagnostic};
+use githook::{explain, githook_git, package_resolver, parse_spanned, tokenize_with_spans, execute_with_context, BlockLevel, Diagnostic, ExecutionContext, HumanReporter, JsonReporter, MISSING_CONFIG_CODE};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
+/// Passing `-` as the hook reads the script from stdin.
+const STDIN_PATH: &str = "-";
+
 #[derive(Parser)]
 #[command(name = "githook")]
 #[command(about = "Git hook language and executor", long_about = None)]
@@ -28,6 +34,63 @@ struct Cli {
     #[arg(long = "skip-group", value_name = "GROUPS")]
     skip_groups: Option<String>,
 
+    #[arg(long)]
+    fix: bool,
+
+    #[arg(long, value_enum, default_value = "human")]
+    format: OutputFormat,
+
+    #[arg(long)]
+    dedupe_warnings: bool,
+
+    #[arg(long)]
+    no_fail_fast: bool,
+
+    #[arg(long)]
+    warn_empty_groups: bool,
+
+    #[arg(short, long)]
+    quiet: bool,
+
+    #[arg(long, value_enum, default_value = "auto", value_name = "WHEN")]
+    color: ColorChoice,
+
+    /// Same as `--color never`
+    #[arg(long)]
+    no_color: bool,
+
+    #[arg(long, value_name = "N")]
+    max_macro_depth: Option<usize>,
+
+    #[arg(long, value_name = "BYTES")]
+    max_content_size: Option<usize>,
+
+    #[arg(long, value_name = "N")]
+    max_warnings: Option<usize>,
+
+    #[arg(long, value_name = "DURATION")]
+    since: Option<String>,
+
+    #[arg(long)]
+    offline: bool,
+
+    #[arg(long, value_name = "SECS")]
+    package_timeout: Option<u64>,
+
+    /// Allow fetching packages that are not installed locally
+    #[arg(long)]
+    allow_network: bool,
+
+    /// Host that package fetches may reach, repeatable
+    #[arg(long = "allow-host", value_name = "HOST")]
+    allowed_hosts: Vec<String>,
+
+    #[arg(long = "ignore", value_name = "GLOB")]
+    ignore_patterns: Vec<String>,
+
+    #[arg(long = "block-on", value_enum, default_value = "critical")]
+    block_on: BlockOn,
+
     #[arg(value_name = "HOOK_TYPE")]
     hook_type: Option<String>,
 
@@ -35,22 +98,110 @@ struct Cli {
     hook_args: Vec<String>,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Human,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BlockOn {
+    Critical,
+    Warning,
+    Info,
+}
+
+impl From<BlockOn> for BlockLevel {
+    fn from(level: BlockOn) -> Self {
+        match level {
+            BlockOn::Critical => BlockLevel::Critical,
+            BlockOn::Warning => BlockLevel::Warning,
+            BlockOn::Info => BlockLevel::Info,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     List,
     CheckUpdate,
     Update,
+    Fmt {
+        #[arg(long)]
+        check: bool,
+
+        #[arg(value_name = "FILES")]
+        files: Vec<PathBuf>,
+    },
+    /// Run a hook against a commit range instead of the index
+    Test {
+        #[arg(value_name = "HOOK")]
+        hook: String,
+
+        #[arg(long, value_name = "REV..REV")]
+        range: String,
+    },
+    /// Print a longer description of an error code
+    Explain {
+        #[arg(value_name = "CODE")]
+        code: String,
+    },
 }
 
-fn main() -> Result<()> {
+/// Exit code when a critical check blocked the hook
+const EXIT_BLOCKED: i32 = 1;
+/// Exit code when only warnings blocked the hook, via `--block-on` or `--max-warnings`
+const EXIT_WARNINGS_AS_ERRORS: i32 = 2;
+/// Exit code when the hook could not be loaded or run
+const EXIT_ERROR: i32 = 3;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {:?}", err);
+        std::process::exit(EXIT_ERROR);
+    }
+}
+
+fn run() -> Result<()> {
     let cli = Cli::parse();
+    let mut hook_type = cli.hook_type;
+
+    // https://no-color.org: any non-empty value disables colors unless they are forced
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if let Some(enabled) = color_override(cli.color, cli.no_color, no_color_env) {
+        colored::control::set_override(enabled);
+    }
 
     if let Some(command) = cli.command {
-        return match command {
-            Commands::List => list_packages(),
-            Commands::CheckUpdate => updater::check_for_updates(),
-            Commands::Update => updater::perform_update(),
-        };
+        match command {
+            Commands::Test { hook, range } => {
+                githook_git::set_diff_range(Some(githook_git::DiffRange::parse(&range)?));
+                hook_type = Some(hook);
+            }
+            Commands::Explain { code } => {
+                match explain(&code) {
+                    Some(text) => println!("{}: {}\n\n{}", "Error".cyan().bold(), code.to_ascii_uppercase(), text),
+                    None => anyhow::bail!("Unknown error code '{}'", code),
+                }
+                return Ok(());
+            }
+            Commands::List => return list_packages(),
+            Commands::CheckUpdate => return updater::check_for_updates(),
+            Commands::Update => return updater::perform_update(),
+            Commands::Fmt { check, files } => {
+                if !fmt::run(files, check)? {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+        }
     }
 
     let only_groups = cli.only_groups.map(|s| {
@@ -61,31 +212,143 @@ fn main() -> Result<()> {
         s.split(',').map(|g| g.trim().to_string()).collect()
     });
 
-    let use_cache = !cli.no_cache && (cli.cache || !cli.no_cache);
+    let use_cache = !cli.no_cache;
 
-    let hook_type = determine_hook_type(cli.hook_type, &cli.hook_args)?;
+    package_resolver::set_offline(cli.offline);
+    package_resolver::set_allow_network(cli.allow_network);
+    package_resolver::set_allowed_hosts(cli.allowed_hosts);
+    githook_git::set_ignore_patterns(cli.ignore_patterns);
+    if let Some(secs) = cli.package_timeout {
+        package_resolver::set_package_fetch_timeout(secs);
+    }
 
-    let config_path = find_config(&hook_type)?;
+    let hook_type = determine_hook_type(hook_type, &cli.hook_args)?;
+    let human = cli.format == OutputFormat::Human;
+
+    let min_interval = cli.since.as_deref().map(last_run::parse_duration).transpose()?;
+    if let Some(interval) = min_interval
+        && last_run::ran_within(&hook_type, interval)?
+    {
+        if human && !cli.quiet {
+            println!("{} Skipping {}, it passed less than {} ago", "-".cyan(), hook_type, cli.since.unwrap_or_default());
+        }
+        std::process::exit(0);
+    }
 
-    if !use_cache {
-        println!(
-            "{} Running {} (cache disabled)...",
-            "-".cyan(),
-            config_path.display()
-        );
+    let config_path = find_config(&hook_type)?;
+    let display_path = if config_path == Path::new(STDIN_PATH) {
+        "<stdin>".to_string()
     } else {
-        println!("{} Running {}...", "-".cyan(), config_path.display());
+        config_path.display().to_string()
+    };
+
+    if human && !cli.quiet {
+        if !use_cache {
+            println!(
+                "{} Running {} (cache disabled)...",
+                "-".cyan(),
+                display_path
+            );
+        } else {
+            println!("{} Running {}...", "-".cyan(), display_path);
+        }
+    }
+
+    let source = read_source(&config_path)?;
+
+    let mut context = ExecutionContext::new_with_filters(only_groups, skip_groups);
+    context.set_allow_fix(cli.fix);
+    context.set_block_on(cli.block_on.into());
+    context.set_dedupe_warnings(cli.dedupe_warnings);
+    context.set_fail_fast(!cli.no_fail_fast);
+    context.set_warn_empty_groups(cli.warn_empty_groups);
+    context.set_max_content_size(cli.max_content_size);
+    context.set_max_warnings(cli.max_warnings);
+    if let Some(depth) = cli.max_macro_depth {
+        context.set_max_macro_depth(depth);
+    }
+    if !human {
+        context.set_reporter(Box::new(JsonReporter::new()));
+    } else if cli.quiet {
+        context.set_reporter(Box::new(HumanReporter::quiet()));
     }
 
-    let source = fs::read_to_string(&config_path)
-        .with_context(|| format!("Failed to read config from {:?}", config_path))?;
+    let status = run_config(&source, &config_path, &cli.hook_args, context)?;
+
+    if min_interval.is_some() && !status.is_blocked() {
+        last_run::record(&hook_type)?;
+    }
+
+    if human {
+        println!("{}", status_line(&status));
+    }
+    std::process::exit(exit_code(&status));
+}
+
+/// Forced color setting, or `None` to let `colored` detect the terminal
+fn color_override(choice: ColorChoice, no_color_flag: bool, no_color_env: bool) -> Option<bool> {
+    match choice {
+        _ if no_color_flag => Some(false),
+        ColorChoice::Always => Some(true),
+        ColorChoice::Auto if !no_color_env => None,
+        _ => Some(false),
+    }
+}
+
+/// Final line printed by the human output
+fn status_line(status: &ExecutionStatus) -> String {
+    match status {
+        ExecutionStatus::Ok => format!("{} Hook passed!", "✓".green()),
+        ExecutionStatus::Warn => format!("{} Hook passed with warnings", "!".yellow()),
+        ExecutionStatus::Block => format!("{} Hook blocked!", "✗".red()),
+        ExecutionStatus::BlockOnWarnings => format!("{} Hook blocked by warnings!", "✗".red()),
+    }
+}
+
+/// Process exit code for the outcome of a hook run
+fn exit_code(status: &ExecutionStatus) -> i32 {
+    match status {
+        ExecutionStatus::Ok | ExecutionStatus::Warn => 0,
+        ExecutionStatus::Block => EXIT_BLOCKED,
+        ExecutionStatus::BlockOnWarnings => EXIT_WARNINGS_AS_ERRORS,
+    }
+}
+
+/// Whether output is styled, after `--color`, `--no-color` and `NO_COLOR` were applied
+fn use_color() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+fn read_source(config_path: &Path) -> Result<String> {
+    read_source_from(config_path, std::io::stdin())
+}
+
+/// Read the hook from `config_path`, or from `stdin` when the path is `-`
+fn read_source_from(config_path: &Path, mut stdin: impl Read) -> Result<String> {
+    if config_path == Path::new(STDIN_PATH) {
+        let mut source = String::new();
+        stdin
+            .read_to_string(&mut source)
+            .context("Failed to read config from stdin")?;
+        return Ok(source);
+    }
 
-    let tokens = match tokenize_with_spans(&source) {
+    fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config from {:?}", config_path))
+}
+
+fn run_config(
+    source: &str,
+    config_path: &Path,
+    hook_args: &[String],
+    context: ExecutionContext,
+) -> Result<ExecutionStatus> {
+    let tokens = match tokenize_with_spans(source) {
         Ok(tokens) => tokens,
         Err(lex_error) => {
-            let diagnostic = Diagnostic::new_lex(&source, lex_error);
+            let diagnostic = Diagnostic::new_lex(source, lex_error).with_color(use_color());
             eprintln!("{}", diagnostic);
-            std::process::exit(1);
+            std::process::exit(EXIT_ERROR);
         }
     };
 
@@ -93,39 +356,27 @@ fn main() -> Result<()> {
     let ast = match parse_spanned(tokens) {
         Ok(ast) => ast.to_vec(),
         Err(parse_error) => {
-            let diagnostic = Diagnostic::new_parse(&source, parse_error);
+            let diagnostic = Diagnostic::new_parse(source, parse_error).with_color(use_color());
             eprintln!("{}", diagnostic);
             eprintln!(
                 "\n{}: Make sure all blocks are properly closed with '{{' and '}}'\n",
                 "Tip".yellow().bold()
             );
-            std::process::exit(1);
+            std::process::exit(EXIT_ERROR);
         }
     };
 
-    validate_config(&ast, &config_path)?;
-
-    let status = execute_with_filters(ast, &cli.hook_args, only_groups, skip_groups)
-        .with_context(|| "Failed to execute hook")?;
+    validate_config(&ast, config_path)?;
 
-    match status {
-        ExecutionStatus::Ok => {
-            println!("{} Hook passed!", "✓".green());
-            std::process::exit(0);
-        }
-        ExecutionStatus::Warn => {
-            println!("{} Hook passed with warnings", "!".yellow());
-            std::process::exit(0);
-        }
-        ExecutionStatus::Block => {
-            println!("{} Hook blocked!", "✗".red());
-            std::process::exit(1);
-        }
-    }
+    execute_with_context(ast, hook_args, context)
+        .with_context(|| "Failed to execute hook")
 }
 
 fn determine_hook_type(explicit_type: Option<String>, args: &[String]) -> Result<String> {
     if let Some(hook_type) = explicit_type {
+        if hook_type == STDIN_PATH {
+            return Ok(hook_type);
+        }
         if hook_type.ends_with(".ghook") && Path::new(&hook_type).exists() {
             return Ok(hook_type);
         }
@@ -140,19 +391,19 @@ fn determine_hook_type(explicit_type: Option<String>, args: &[String]) -> Result
         }
     }
 
-    if let Ok(current_exe) = std::env::current_exe() {
-        if let Some(file_name) = current_exe.file_name() {
-            let name = file_name.to_string_lossy();
+    if let Ok(current_exe) = std::env::current_exe()
+        && let Some(file_name) = current_exe.file_name()
+    {
+        let name = file_name.to_string_lossy();
 
-            if name.contains("pre-commit") {
-                return Ok("pre-commit".to_string());
-            } else if name.contains("commit-msg") {
-                return Ok("commit-msg".to_string());
-            } else if name.contains("pre-push") {
-                return Ok("pre-push".to_string());
-            } else if name.contains("post-commit") {
-                return Ok("post-commit".to_string());
-            }
+        if name.contains("pre-commit") {
+            return Ok("pre-commit".to_string());
+        } else if name.contains("commit-msg") {
+            return Ok("commit-msg".to_string());
+        } else if name.contains("pre-push") {
+            return Ok("pre-push".to_string());
+        } else if name.contains("post-commit") {
+            return Ok("post-commit".to_string());
         }
     }
 
@@ -174,6 +425,10 @@ fn is_valid_hook_type(hook: &str) -> bool {
 }
 
 fn find_config(hook_type: &str) -> Result<PathBuf> {
+    if hook_type == STDIN_PATH {
+        return Ok(PathBuf::from(STDIN_PATH));
+    }
+
     if hook_type.ends_with(".ghook") {
         let path = PathBuf::from(hook_type);
         if path.exists() {
@@ -183,27 +438,54 @@ fn find_config(hook_type: &str) -> Result<PathBuf> {
         }
     }
     
-    let locations = vec![
-        PathBuf::from(format!(".githook/{}.ghook", hook_type)),
-        PathBuf::from(format!(".git/hooks/{}.ghook", hook_type)),
-        PathBuf::from(format!("{}.ghook", hook_type)),
-    ];
-    
-    for path in locations {
-        if path.exists() {
-            return Ok(path);
-        }
+    let cwd = std::env::current_dir().context("Failed to determine current directory")?;
+    if let Some(path) = search_config(&cwd, hook_type) {
+        return Ok(path);
     }
     
     anyhow::bail!(
-        "Could not find config file for hook '{}'. Looked in:\n  - .githook/{}.ghook\n  - .git/hooks/{}.ghook\n  - {}.ghook",
+        "[{}] Could not find config file for hook '{}'. Looked in (current directory and parents up to the repository root):\n  - .githook/{}.ghook\n  - .git/hooks/{}.ghook\n  - {}.ghook\n\nRun `githook explain {}` for more information",
+        MISSING_CONFIG_CODE,
+        hook_type,
         hook_type,
         hook_type,
         hook_type,
-        hook_type
+        MISSING_CONFIG_CODE
     )
 }
 
+/// Look for the hook file in `cwd`, then in its parents up to the repository root.
+/// Matches in `cwd` itself are returned relative to it.
+fn search_config(cwd: &Path, hook_type: &str) -> Option<PathBuf> {
+    let locations = [
+        PathBuf::from(format!(".githook/{}.ghook", hook_type)),
+        PathBuf::from(format!(".git/hooks/{}.ghook", hook_type)),
+        PathBuf::from(format!("{}.ghook", hook_type)),
+    ];
+    
+    if let Some(location) = locations.iter().find(|location| cwd.join(location).exists()) {
+        return Some(location.clone());
+    }
+
+    // Hooks may run from a subdirectory, so walk up until the repository root
+    if cwd.join(".git").exists() {
+        return None;
+    }
+    for dir in cwd.ancestors().skip(1) {
+        for location in &locations {
+            let path = dir.join(location);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
 fn list_packages() -> Result<()> {
     let home = dirs::home_dir()
         .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
@@ -312,4 +594,178 @@ fn validate_config(ast: &[Statement], _config_path: &Path) -> Result<()> {
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use githook::{Reporter, Span};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Mutex;
+
+    /// Tests that change the global color override run one at a time
+    static COLOR_OVERRIDE: Mutex<()> = Mutex::new(());
+
+    const STATUSES: [ExecutionStatus; 4] = [
+        ExecutionStatus::Ok,
+        ExecutionStatus::Warn,
+        ExecutionStatus::Block,
+        ExecutionStatus::BlockOnWarnings,
+    ];
+
+    /// Keeps warnings and blocks instead of printing them
+    struct CapturingReporter {
+        events: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Reporter for CapturingReporter {
+        fn group(&mut self, _name: &str, _severity: &str) {}
+        fn group_result(&mut self, _name: &str, _passed: bool, _span: &Span) {}
+        fn check(&mut self, _description: &str) {}
+        fn warning(&mut self, message: &str) {
+            self.events.borrow_mut().push(format!("warning: {}", message));
+        }
+        fn block(&mut self, message: &str) {
+            self.events.borrow_mut().push(format!("block: {}", message));
+        }
+        fn detail(&mut self, _line: &str) {}
+        fn note(&mut self, _message: &str) {}
+        fn summary(&mut self, _context: &ExecutionContext, _status: &ExecutionStatus) {}
+    }
+
+    fn run_quietly(source: &str, configure: impl FnOnce(&mut ExecutionContext)) -> ExecutionStatus {
+        let mut context = ExecutionContext::new();
+        context.set_reporter(Box::new(HumanReporter::quiet()));
+        configure(&mut context);
+        run_config(source, Path::new("test.ghook"), &[], context).unwrap()
+    }
+
+    #[test]
+    fn maps_each_status_to_its_exit_code() {
+        assert_eq!(exit_code(&ExecutionStatus::Ok), 0);
+        assert_eq!(exit_code(&ExecutionStatus::Warn), 0);
+        assert_eq!(exit_code(&ExecutionStatus::Block), EXIT_BLOCKED);
+        assert_eq!(exit_code(&ExecutionStatus::BlockOnWarnings), EXIT_WARNINGS_AS_ERRORS);
+    }
+
+    #[test]
+    fn warnings_alone_pass() {
+        let status = run_quietly("warn_if true message \"a\"\n", |_| {});
+        assert_eq!(exit_code(&status), 0);
+    }
+
+    #[test]
+    fn too_many_warnings_exit_with_warnings_as_errors() {
+        let source = "warn_if true message \"a\"\nwarn_if true message \"b\"\n";
+        let status = run_quietly(source, |context| context.set_max_warnings(Some(1)));
+        assert_eq!(exit_code(&status), EXIT_WARNINGS_AS_ERRORS);
+    }
+
+    #[test]
+    fn max_warnings_blocks_only_above_the_limit() {
+        let warnings = |count: usize| {
+            (0..count)
+                .map(|i| format!("warn_if true message \"w{i}\"\n"))
+                .collect::<String>()
+        };
+
+        for (count, expected) in [(1, 0), (2, 0), (3, EXIT_WARNINGS_AS_ERRORS)] {
+            let status = run_quietly(&warnings(count), |context| context.set_max_warnings(Some(2)));
+            assert_eq!(exit_code(&status), expected, "{count} warnings with a limit of 2");
+        }
+
+        let status = run_quietly(&warnings(3), |context| context.set_max_warnings(None));
+        assert_eq!(exit_code(&status), 0);
+    }
+
+    #[test]
+    fn blocking_warning_group_exits_with_warnings_as_errors() {
+        let source = "group style {\n  severity: warning\n  block_if true message \"x\"\n}\n";
+        let status = run_quietly(source, |context| context.set_block_on(BlockLevel::Warning));
+        assert_eq!(exit_code(&status), EXIT_WARNINGS_AS_ERRORS);
+
+        let status = run_quietly(&source.replace("warning", "critical"), |context| {
+            context.set_block_on(BlockLevel::Warning)
+        });
+        assert_eq!(exit_code(&status), EXIT_BLOCKED);
+    }
+
+    #[test]
+    fn script_piped_through_stdin_runs() {
+        let stdin = "run \"true\"\nwarn_if true message \"from stdin\"\n".as_bytes();
+        let source = read_source_from(Path::new(STDIN_PATH), stdin).unwrap();
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut context = ExecutionContext::new();
+        context.set_reporter(Box::new(CapturingReporter { events: Rc::clone(&events) }));
+        let status = run_config(&source, Path::new(STDIN_PATH), &[], context).unwrap();
+
+        assert_eq!(status, ExecutionStatus::Warn);
+        assert_eq!(events.take(), vec!["warning: from stdin"]);
+    }
+
+    #[test]
+    fn stdin_is_only_read_for_the_dash_path() {
+        let err = read_source_from(Path::new("missing.ghook"), "run \"a\"".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("Failed to read config from \"missing.ghook\""));
+    }
+
+    #[test]
+    fn config_is_found_from_a_nested_subdirectory() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".git")).unwrap();
+        fs::create_dir_all(repo.path().join(".githook")).unwrap();
+        fs::write(repo.path().join(".githook/pre-commit.ghook"), "run \"true\"\n").unwrap();
+        let nested = repo.path().join("crates/app/src");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(search_config(&nested, "pre-commit"), Some(repo.path().join(".githook/pre-commit.ghook")));
+        assert_eq!(search_config(repo.path(), "pre-commit"), Some(PathBuf::from(".githook/pre-commit.ghook")));
+        assert_eq!(search_config(&nested, "pre-push"), None);
+    }
+
+    #[test]
+    fn config_search_stops_at_the_repository_root() {
+        let outer = tempfile::tempdir().unwrap();
+        fs::write(outer.path().join("pre-commit.ghook"), "run \"true\"\n").unwrap();
+        let repo = outer.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("src")).unwrap();
+
+        assert_eq!(search_config(&repo.join("src"), "pre-commit"), None);
+    }
+
+    #[test]
+    fn no_color_flag_and_env_disable_colors() {
+        assert_eq!(color_override(ColorChoice::Auto, true, false), Some(false));
+        assert_eq!(color_override(ColorChoice::Always, true, false), Some(false));
+        assert_eq!(color_override(ColorChoice::Auto, false, true), Some(false));
+        assert_eq!(color_override(ColorChoice::Auto, false, false), None);
+    }
+
+    #[test]
+    fn disabled_colors_render_status_without_escapes() {
+        let _guard = COLOR_OVERRIDE.lock().unwrap();
+        colored::control::set_override(false);
+        for status in &STATUSES {
+            assert!(!status_line(status).contains('\x1b'), "{:?}", status);
+        }
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn always_forces_escapes_and_never_strips_them() {
+        assert_eq!(color_override(ColorChoice::Always, false, true), Some(true));
+        assert_eq!(color_override(ColorChoice::Never, false, false), Some(false));
+
+        let _guard = COLOR_OVERRIDE.lock().unwrap();
+        // Test output is captured, so stdout is not a terminal here
+        colored::control::set_override(color_override(ColorChoice::Always, false, false).unwrap());
+        assert!(status_line(&ExecutionStatus::Block).contains("\x1b["));
+
+        colored::control::set_override(color_override(ColorChoice::Never, false, false).unwrap());
+        assert!(!status_line(&ExecutionStatus::Block).contains('\x1b'));
+        colored::control::unset_override();
+    }
+}
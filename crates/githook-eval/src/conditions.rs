@@ -3,7 +3,6 @@ use crate::executor::execute_macro_call;
 use anyhow::Result;
 use githook_syntax::BlockCondition;
 use githook_syntax::*;
-use colored::*;
 use regex::Regex;
 use std::borrow::Cow;
 use std::sync::OnceLock;
@@ -19,7 +18,7 @@ fn get_regex_cache_size() -> usize {
         .unwrap_or(DEFAULT_CACHE_SIZE)
 }
 
-fn get_cached_regex(pattern: &str) -> Result<Regex> {
+pub(crate) fn get_cached_regex(pattern: &str) -> Result<Regex> {
     let cache = REGEX_CACHE.get_or_init(|| {
         let size = get_regex_cache_size();
         std::sync::Mutex::new(
@@ -115,11 +114,19 @@ fn evaluate_comparison(
             })
         }
         
-        (ModifiedLines(..) | FilesChanged(..) | Additions(..) | Deletions(..) | CommitsAhead(..), 
+        (ModifiedLines(..) | FilesChanged(..) | StagedCount(..) | Additions(..) | Deletions(..) | CommitsAhead(..) | SubjectLength(..) | MaxLineLength(..), 
          Greater | GreaterOrEqual | Less | LessOrEqual | Equals) => {
             let value = match left {
+                ModifiedLines(Some(pattern), _) => {
+                    let stats = githook_git::get_diff_stats_for(pattern)?;
+                    (stats.additions + stats.deletions) as f64
+                }
+                FilesChanged(Some(pattern), _) => githook_git::get_diff_stats_for(pattern)?.files_changed as f64,
+                Additions(Some(pattern), _) => githook_git::get_diff_stats_for(pattern)?.additions as f64,
+                Deletions(Some(pattern), _) => githook_git::get_diff_stats_for(pattern)?.deletions as f64,
                 ModifiedLines(..) => githook_git::get_modified_lines()? as f64,
                 FilesChanged(..) => githook_git::get_diff_stats()?.files_changed as f64,
+                StagedCount(..) => context.staged_files("*")?.len() as f64,
                 Additions(..) => githook_git::get_diff_stats()?.additions as f64,
                 Deletions(..) => githook_git::get_diff_stats()?.deletions as f64,
                 CommitsAhead(..) => {
@@ -127,6 +134,8 @@ fn evaluate_comparison(
                     let remote_branch = format!("origin/{}", branch);
                     githook_git::get_commits_ahead(&remote_branch)? as f64
                 }
+                SubjectLength(..) => githook_git::subject_length(&context.get_commit_message()?) as f64,
+                MaxLineLength(..) => githook_git::max_body_line_length(&context.get_commit_message()?) as f64,
                 _ => unreachable!(),
             };
             
@@ -152,11 +161,12 @@ fn evaluate_comparison(
             })
         }
         
-        (BranchName(..) | Content(..) | Diff(..) | CommitMessage(..), Matches) => {
+        (BranchName(..) | Content(..) | Diff(..) | AddedLines(..) | CommitMessage(..), Matches) => {
             let text = match left {
                 BranchName(..) => githook_git::get_branch_name()?,
                 Content(..) => context.current_file_content()?,
-                Diff(..) => context.current_file_diff()?,
+                Diff(..) => context.diff_text()?,
+                AddedLines(..) => context.added_lines_text()?,
                 CommitMessage(..) => context.get_commit_message()?,
                 _ => unreachable!(),
             };
@@ -170,11 +180,12 @@ fn evaluate_comparison(
             Ok(regex.is_match(&text))
         }
         
-        (BranchName(..) | Content(..) | Diff(..) | CommitMessage(..) | Extension(..) | Filename(..) | Basename(..) | Dirname(..) | EnvVar(_, _) | Placeholder(_, _), Equals) => {
+        (BranchName(..) | Content(..) | Diff(..) | AddedLines(..) | CommitMessage(..) | Extension(..) | Filename(..) | Basename(..) | Dirname(..) | EnvVar(_, _) | Placeholder(_, _), Equals) => {
             let text = match left {
                 BranchName(..) => githook_git::get_branch_name()?,
                 Content(..) => context.current_file_content()?,
-                Diff(..) => context.current_file_diff()?,
+                Diff(..) => context.diff_text()?,
+                AddedLines(..) => context.added_lines_text()?,
                 CommitMessage(..) => context.get_commit_message()?,
                 Extension(..) => resolve_value("extension", context).into_owned(),
                 Filename(..) => {
@@ -212,11 +223,25 @@ fn evaluate_comparison(
             Ok(text == *needle)
         }
 
-        (BranchName(..) | Content(..) | Diff(..) | CommitMessage(..) | Extension(..) | Filename(..) | Basename(..) | Dirname(..) | EnvVar(_, _) | Placeholder(_, _), Contains) => {
+        (Diff(..) | AddedLines(..), Contains) if context.current_file().is_none() => {
+            let needle = match right {
+                ComparisonValue::String(s, _) => s,
+                ComparisonValue::Identifier(id, _) => id,
+                _ => anyhow::bail!("contains operator requires string value"),
+            };
+
+            match left {
+                Diff(..) => githook_git::diff_contains(needle),
+                _ => githook_git::diff_added_contains(needle),
+            }
+        }
+
+        (BranchName(..) | Content(..) | Diff(..) | AddedLines(..) | CommitMessage(..) | Extension(..) | Filename(..) | Basename(..) | Dirname(..) | EnvVar(_, _) | Placeholder(_, _), Contains) => {
             let text = match left {
                 BranchName(..) => githook_git::get_branch_name()?,
                 Content(..) => context.current_file_content()?,
-                Diff(..) => context.current_file_diff()?,
+                Diff(..) => context.diff_text()?,
+                AddedLines(..) => context.added_lines_text()?,
                 CommitMessage(..) => context.get_commit_message()?,
                 Extension(..) => {
                     resolve_value("extension", context).into_owned()
@@ -271,7 +296,7 @@ fn evaluate_comparison(
             let items = match context.get_string_list(list_name) {
                 Some(xs) => xs,
                 None => {
-                    println!("  {} unknown string list '{}'", "x".red(), list_name.red());
+                    context.reporter().block(&format!("unknown string list '{}'", list_name));
                     return Ok(false);
                 }
             };
@@ -297,9 +322,9 @@ pub fn evaluate_block_condition(
         BlockCondition::ContainsSecrets(_) => {
             let findings = githook_git::secrets_with_locations()?;
             if !findings.is_empty() {
-                println!("  {} Secrets detected!", "x".red());
+                context.reporter().block("Secrets detected!");
                 for f in &findings {
-                    println!("    {}:{} {}", f.file, f.line, f.line_content.trim());
+                    context.reporter().detail(&format!("{}:{} {}", f.file, f.line, f.line_content.trim()));
                 }
                 return Ok(true);
             }
@@ -354,7 +379,7 @@ pub fn evaluate_block_condition(
             let items = match context.get_string_list(list) {
                 Some(xs) => xs,
                 None => {
-                    println!("  {} unknown string list '{}'", "x".red(), list.red());
+                    context.reporter().block(&format!("unknown string list '{}'", list));
                     return Ok(false);
                 }
             };
@@ -366,6 +391,37 @@ pub fn evaluate_block_condition(
             Ok(githook_git::is_author_set()?)
         }
 
+        BlockCondition::IsBinary(_) => {
+            match context.current_file() {
+                Some(file) => githook_git::is_file_binary(file),
+                None => Ok(false),
+            }
+        }
+
+        BlockCondition::AnyBinary(_) => {
+            Ok(!githook_git::get_binary_staged_files()?.is_empty())
+        }
+
+        BlockCondition::MergeInProgress(_) => {
+            githook_git::is_merge_in_progress()
+        }
+
+        BlockCondition::HasConflicts(_) => {
+            Ok(!githook_git::get_conflict_files("*")?.is_empty())
+        }
+
+        BlockCondition::TagExists(name, _) => {
+            githook_git::tag_exists(name)
+        }
+
+        BlockCondition::EmptyCommit(_) => {
+            githook_git::is_empty_commit()
+        }
+
+        BlockCondition::WorktreeDirty(_) => {
+            Ok(!githook_git::is_worktree_clean()?)
+        }
+
         BlockCondition::AuthorEmailSet(_) => {
             Ok(githook_git::is_author_email_set()?)
         }
@@ -382,6 +438,17 @@ pub fn evaluate_block_condition(
             Ok(actual == *expected)
         }
 
+        BlockCondition::EnvNotEquals(key, expected, _) => {
+            // Unset variables compare as empty
+            let actual = std::env::var(key).unwrap_or_default();
+            Ok(actual != *expected)
+        }
+
+        BlockCondition::EnvContains(key, needle, _) => {
+            let actual = std::env::var(key).unwrap_or_default();
+            Ok(actual.contains(needle.as_str()))
+        }
+
         BlockCondition::MacroCall { name, args, .. } => {
             execute_macro_call(None, name, args, context, hook_args)
         }
@@ -1,12 +1,15 @@
 use anyhow::{Context, Result, anyhow, bail};
-use githook_syntax::{Statement, tokenize_with_spans};
-use std::collections::HashMap;
+use githook_syntax::{GroupSeverity, Span, Statement, tokenize_with_spans};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use crate::stdlib;
+use crate::executor::ExecutionStatus;
+use crate::reporter::{HumanReporter, Reporter};
 
 const MAX_WARNINGS: usize = 1000;
 const MAX_WARNINGS_PER_FILE: usize = 100;
 const MAX_CHECKS_PASSED: usize = 10000;
+const DEFAULT_MAX_MACRO_DEPTH: usize = 64;
 
 type PlaceholderResolver = Box<dyn Fn(&ExecutionContext) -> Option<String> + Send + Sync>;
 type NamespaceRegistry = HashMap<String, PlaceholderResolver>;
@@ -17,6 +20,32 @@ pub struct MacroDefinition {
     pub body: Vec<Statement>,
 }
 
+/// A check that passed, with the span of the group (or top-level statement) it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub description: String,
+    pub span: Option<Span>,
+}
+
+/// Lowest group severity whose failure still blocks the hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum BlockLevel {
+    Info,
+    Warning,
+    #[default]
+    Critical,
+}
+
+impl BlockLevel {
+    pub fn of(severity: Option<&GroupSeverity>) -> Self {
+        match severity {
+            Some(GroupSeverity::Info(_)) => BlockLevel::Info,
+            Some(GroupSeverity::Warning(_)) => BlockLevel::Warning,
+            Some(GroupSeverity::Critical(_)) | None => BlockLevel::Critical,
+        }
+    }
+}
+
 pub struct PlaceholderRegistry {
     namespaces: HashMap<String, NamespaceRegistry>,
 }
@@ -83,6 +112,12 @@ impl PlaceholderRegistry {
             ctx.get_file_size().map(|s| format!("{:.2}", s as f64 / 1024.0 / 1024.0))
         }) as PlaceholderResolver);
         
+        file_ns.insert("is_lfs".to_string(), Box::new(|ctx: &ExecutionContext| {
+            ctx.current_file()
+                .and_then(|f| githook_git::is_lfs_file(f).ok())
+                .map(|lfs| lfs.to_string())
+        }) as PlaceholderResolver);
+
         self.namespaces.insert("file".to_string(), file_ns);
         
         let mut git_ns = HashMap::new();
@@ -98,6 +133,28 @@ impl PlaceholderRegistry {
             githook_git::get_author_email().ok()
         }) as PlaceholderResolver);
         
+        git_ns.insert("commits_ahead".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            let branch = githook_git::get_branch_name().ok()?;
+            githook_git::get_commits_ahead(&format!("origin/{}", branch))
+                .ok()
+                .map(|n| n.to_string())
+        }) as PlaceholderResolver);
+
+        git_ns.insert("unpushed_commits".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            let branch = githook_git::get_branch_name().ok()?;
+            githook_git::get_unpushed_commits(&format!("origin/{}", branch))
+                .ok()
+                .map(|commits| commits.join("\n"))
+        }) as PlaceholderResolver);
+        
+        git_ns.insert("tags".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_tags().ok().map(|tags| tags.join(", "))
+        }) as PlaceholderResolver);
+
+        git_ns.insert("latest_tag".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_latest_tag().ok()
+        }) as PlaceholderResolver);
+
         self.namespaces.insert("git".to_string(), git_ns);
         
         let mut commit_ns = HashMap::new();
@@ -115,6 +172,16 @@ impl PlaceholderRegistry {
                 Some(len.to_string())
             } else { None }
         }) as PlaceholderResolver);
+        commit_ns.insert("subject_length".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_commit_message()
+                .ok()
+                .map(|msg| githook_git::subject_length(&msg).to_string())
+        }) as PlaceholderResolver);
+        commit_ns.insert("max_line_length".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_commit_message()
+                .ok()
+                .map(|msg| githook_git::max_body_line_length(&msg).to_string())
+        }) as PlaceholderResolver);
         commit_ns.insert("lines".to_string(), Box::new(|_ctx: &ExecutionContext| {
             if let Ok(msg) = githook_git::get_commit_message() {
                 Some(msg.lines().count().to_string())
@@ -125,6 +192,9 @@ impl PlaceholderRegistry {
                 Some((msg.contains("Co-authored-by:")).to_string())
             } else { None }
         }) as PlaceholderResolver);
+        commit_ns.insert("is_empty".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::is_empty_commit().ok().map(|empty| empty.to_string())
+        }) as PlaceholderResolver);
         self.namespaces.insert("commit".to_string(), commit_ns);
 
         let mut repo_ns = HashMap::new();
@@ -142,6 +212,15 @@ impl PlaceholderRegistry {
         repo_ns.insert("remote".to_string(), Box::new(|_ctx: &ExecutionContext| {
             githook_git::get_remote_url().ok()
         }) as PlaceholderResolver);
+        repo_ns.insert("remote_host".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_remote_info().ok().map(|info| info.host)
+        }) as PlaceholderResolver);
+        repo_ns.insert("remote_owner".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_remote_info().ok().map(|info| info.owner)
+        }) as PlaceholderResolver);
+        repo_ns.insert("remote_repo".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_remote_info().ok().map(|info| info.repo)
+        }) as PlaceholderResolver);
         self.namespaces.insert("repo".to_string(), repo_ns);
 
         let mut system_ns = HashMap::new();
@@ -180,9 +259,18 @@ impl PlaceholderRegistry {
                 Err(_) => None,
             }
         }) as PlaceholderResolver);
+        diff_ns.insert("is_empty".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_diff_stats().ok().map(|stats| (stats.files_changed == 0).to_string())
+        }) as PlaceholderResolver);
         diff_ns.insert("modified_lines".to_string(), Box::new(|_ctx: &ExecutionContext| {
             githook_git::get_modified_lines().ok().map(|n| n.to_string())
         }) as PlaceholderResolver);
+        diff_ns.insert("largest_file".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_largest_staged_file().ok().flatten().map(|(file, _)| file)
+        }) as PlaceholderResolver);
+        diff_ns.insert("largest_file_size".to_string(), Box::new(|_ctx: &ExecutionContext| {
+            githook_git::get_largest_staged_file().ok().flatten().map(|(_, size)| size.to_string())
+        }) as PlaceholderResolver);
         self.namespaces.insert("diff".to_string(), diff_ns);
 
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -206,6 +294,10 @@ impl PlaceholderRegistry {
                 ctx.current_file()
                     .and_then(|f| githook_git::get_staged_blob_oid(f).ok())
             }) as PlaceholderResolver);
+            file_ns.insert("diff".to_string(), Box::new(|ctx: &ExecutionContext| {
+                ctx.current_file()
+                    .and_then(|f| githook_git::get_file_diff(f).ok())
+            }) as PlaceholderResolver);
         }
     }
 
@@ -222,8 +314,16 @@ impl PlaceholderRegistry {
 }
 
 pub struct ExecutionContext {
-    warnings: HashMap<String, Vec<String>>,
-    checks_passed: Vec<String>,
+    warnings: Vec<(String, Vec<String>)>,
+    warning_index: HashMap<String, usize>,
+    dedupe_warnings: bool,
+    fail_fast: bool,
+    warn_empty_groups: bool,
+    max_content_size: Option<usize>,
+    max_warnings: Option<usize>,
+    warning_blocks: usize,
+    checks_passed: Vec<CheckResult>,
+    check_span: Option<Span>,
     checks_failed: Vec<String>,
     checks_run: usize,
     current_file_pattern: Option<String>,
@@ -231,6 +331,11 @@ pub struct ExecutionContext {
     allowed_commands: Vec<String>,
     macros: HashMap<String, MacroDefinition>,
     macro_params: HashMap<String, String>,
+    macro_stack: Vec<String>,
+    max_macro_depth: usize,
+    import_stack: Vec<String>,
+    reported_deprecations: HashSet<String>,
+    imported_files: HashSet<String>,
     std_macros: HashMap<String, MacroDefinition>,
     string_lists: HashMap<String, Vec<String>>,
     vars: HashMap<String, String>,
@@ -240,6 +345,9 @@ pub struct ExecutionContext {
     staged_files_cache: Option<Vec<String>>,
     allowed_groups: Option<Vec<String>>,
     skipped_groups: Option<Vec<String>>,
+    allow_fix: bool,
+    block_on: BlockLevel,
+    reporter: Box<dyn Reporter>,
     placeholder_registry: PlaceholderRegistry,
 }
 
@@ -250,8 +358,16 @@ impl ExecutionContext {
     
     pub fn new_with_filters(allowed_groups: Option<Vec<String>>, skipped_groups: Option<Vec<String>>) -> Self {
         let mut context = Self {
-            warnings: HashMap::new(),
+            warnings: Vec::new(),
+            warning_index: HashMap::new(),
+            dedupe_warnings: false,
+            fail_fast: true,
+            warn_empty_groups: false,
+            max_content_size: None,
+            max_warnings: None,
+            warning_blocks: 0,
             checks_passed: Vec::new(),
+            check_span: None,
             checks_failed: Vec::new(),
             checks_run: 0,
             current_file_pattern: None,
@@ -264,11 +380,19 @@ impl ExecutionContext {
             macros: HashMap::new(),
             placeholder_registry: PlaceholderRegistry::new(),
             macro_params: HashMap::new(),
+            macro_stack: Vec::new(),
+            max_macro_depth: DEFAULT_MAX_MACRO_DEPTH,
+            import_stack: Vec::new(),
+            reported_deprecations: HashSet::new(),
+            imported_files: HashSet::new(),
             std_macros: HashMap::new(),
             string_lists: HashMap::new(),
             vars: HashMap::new(),
             allowed_groups,
             skipped_groups,
+            allow_fix: false,
+            block_on: BlockLevel::default(),
+            reporter: Box::new(HumanReporter::new()),
         };
         context.load_stdlib()
             .expect("Standard library must load successfully");
@@ -300,8 +424,7 @@ impl ExecutionContext {
         }
 
         let path = self.current_file_path()?;
-        let spec = format!(":{}", path);
-        let out = githook_git::git_capture(&["show", &spec])?;
+        let out = self.file_content(path)?;
 
         self.current_file_content_cache = Some(Arc::new(out.clone()));
         Ok(out)
@@ -313,12 +436,28 @@ impl ExecutionContext {
         }
 
         let path = self.current_file_path()?;
-        let out = githook_git::git_capture(&["diff", "--cached", "--", path])?;
+        let out = githook_git::get_file_diff(path)?;
         
         self.current_file_diff_cache = Some(Arc::new(out.clone()));
         Ok(out)
     }
 
+    pub fn diff_text(&mut self) -> Result<String> {
+        if self.current_file.is_some() {
+            self.current_file_diff()
+        } else {
+            githook_git::get_staged_diff()
+        }
+    }
+
+    pub fn added_lines_text(&mut self) -> Result<String> {
+        if self.current_file.is_none() {
+            return githook_git::get_diff_added_lines();
+        }
+
+        Ok(githook_git::added_lines(&self.current_file_diff()?))
+    }
+
     pub fn get_commit_message(&mut self) -> Result<String> {
         if let Some(cached) = &self.commit_message_cache {
             return Ok((**cached).clone());
@@ -355,50 +494,246 @@ impl ExecutionContext {
         self.macro_params.clear();
     }
 
+    pub fn enter_macro(&mut self, name: String) -> Result<()> {
+        if self.macro_stack.len() >= self.max_macro_depth {
+            bail!(
+                "Macro call depth limit ({}) exceeded while calling @{} (is it recursive?)",
+                self.max_macro_depth,
+                name
+            );
+        }
+        self.macro_stack.push(name);
+        Ok(())
+    }
+
+    pub fn set_max_macro_depth(&mut self, depth: usize) {
+        self.max_macro_depth = depth;
+    }
+
+    pub fn leave_macro(&mut self) {
+        self.macro_stack.pop();
+    }
+
+    pub fn enter_import(&mut self, path: String) -> Result<()> {
+        if let Some(pos) = self.import_stack.iter().position(|p| *p == path) {
+            let mut chain = self.import_stack[pos..].to_vec();
+            chain.push(path);
+            bail!("Import cycle detected: {}", chain.join(" -> "));
+        }
+        self.import_stack.push(path);
+        Ok(())
+    }
+
+    pub fn leave_import(&mut self) {
+        self.import_stack.pop();
+    }
+
+    /// Returns true the first time `path` is imported.
+    pub fn mark_imported(&mut self, path: String) -> bool {
+        self.imported_files.insert(path)
+    }
+
+    pub fn current_macro(&self) -> Option<&str> {
+        self.macro_stack.last().map(|s| s.as_str())
+    }
+
+    /// Returns true the first time a deprecation is reported for `macro_name`.
+    pub fn mark_deprecation_reported(&mut self, macro_name: &str) -> bool {
+        self.reported_deprecations.insert(macro_name.to_string())
+    }
+
+    /// Records a warning and reports it. Warnings are kept in the order they
+    /// were first raised; with dedupe enabled a repeated message is only reported once.
     pub fn warn(&mut self, msg: String) {
-        if self.warnings.len() >= MAX_WARNINGS {
-            eprintln!("Warning: Maximum warning limit ({}) reached, dropping new warnings", MAX_WARNINGS);
-            return;
+        self.report_warning(&msg);
+        self.record_warning(msg);
+    }
+
+    /// Reports a warning the way [`ExecutionContext::warn`] would, without recording it yet
+    pub fn report_warning(&mut self, msg: &str) {
+        let seen = self.warning_index.contains_key(msg);
+        let report = if seen { !self.dedupe_warnings } else { self.warnings.len() < MAX_WARNINGS };
+        if report {
+            self.reporter.warning(msg);
         }
-        
+    }
+
+    /// Records a warning already shown with [`ExecutionContext::report_warning`]
+    pub fn record_warning(&mut self, msg: String) {
+        let index = match self.warning_index.get(&msg) {
+            Some(&index) => index,
+            None => {
+                if self.warnings.len() >= MAX_WARNINGS {
+                    eprintln!("Warning: Maximum warning limit ({}) reached, dropping new warnings", MAX_WARNINGS);
+                    return;
+                }
+                self.warning_index.insert(msg.clone(), self.warnings.len());
+                self.warnings.push((msg, Vec::new()));
+                self.warnings.len() - 1
+            }
+        };
+
         if let Some(file) = &self.current_file {
-            let locations = self.warnings
-                .entry(msg)
-                .or_default();
-            
+            let locations = &mut self.warnings[index].1;
             if locations.len() < MAX_WARNINGS_PER_FILE {
                 locations.push(file.clone());
             }
-        } else {
-            self.warnings.entry(msg).or_default();
         }
     }
 
+    /// Block the hook once more than `max` distinct warnings were raised
+    pub fn set_max_warnings(&mut self, max: Option<usize>) {
+        self.max_warnings = max;
+    }
+
+    pub fn max_warnings(&self) -> Option<usize> {
+        self.max_warnings
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.warnings.len()
+    }
+
+    pub fn exceeds_max_warnings(&self) -> bool {
+        self.max_warnings.is_some_and(|max| self.warning_count() > max)
+    }
+
+    /// Count a non-critical group that blocked because of `--block-on`
+    pub fn record_warning_block(&mut self) {
+        self.warning_blocks += 1;
+    }
+
+    pub fn warning_blocks(&self) -> usize {
+        self.warning_blocks
+    }
+
+    pub fn set_dedupe_warnings(&mut self, dedupe: bool) {
+        self.dedupe_warnings = dedupe;
+    }
+
+    /// When disabled, a failing top-level statement is recorded and execution continues
+    pub fn set_fail_fast(&mut self, fail_fast: bool) {
+        self.fail_fast = fail_fast;
+    }
+
+    pub fn fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    /// Report groups that executed no checks as warnings instead of notes
+    pub fn set_warn_empty_groups(&mut self, warn: bool) {
+        self.warn_empty_groups = warn;
+    }
+
+    pub fn warn_empty_groups(&self) -> bool {
+        self.warn_empty_groups
+    }
+
+    /// Refuse to load staged files larger than `bytes` for content checks
+    pub fn set_max_content_size(&mut self, bytes: Option<usize>) {
+        self.max_content_size = bytes;
+    }
+
+    pub fn max_content_size(&self) -> Option<usize> {
+        self.max_content_size
+    }
+
+    /// Staged content of `file`, checking its size against the limit before loading it
+    pub fn file_content(&self, file: &str) -> Result<String> {
+        if let Some(limit) = self.max_content_size {
+            let size = githook_git::get_staged_file_size_from_index(file)?;
+            if size > limit {
+                anyhow::bail!(
+                    "{} is {} bytes, larger than the content size limit of {} bytes (see --max-content-size)",
+                    file,
+                    size,
+                    limit
+                );
+            }
+        }
+
+        githook_git::get_staged_file_content_from_index(file)
+    }
+
     pub fn check_passed(&mut self, msg: String) {
+        self.reporter.check(&msg);
         if self.checks_passed.len() < MAX_CHECKS_PASSED {
-            self.checks_passed.push(msg);
+            self.checks_passed.push(CheckResult {
+                description: msg,
+                span: self.check_span,
+            });
         }
     }
 
+    /// Span attached to checks that pass from now on. Returns the previous one.
+    pub fn set_check_span(&mut self, span: Option<Span>) -> Option<Span> {
+        std::mem::replace(&mut self.check_span, span)
+    }
+
     pub fn check_run(&mut self) {
         self.checks_run += 1;
     }
 
+    pub fn set_reporter(&mut self, reporter: Box<dyn Reporter>) {
+        self.reporter = reporter;
+    }
+
+    pub fn reporter(&mut self) -> &mut dyn Reporter {
+        self.reporter.as_mut()
+    }
+
+    pub fn report_summary(&mut self, status: &ExecutionStatus) {
+        let mut reporter = std::mem::replace(&mut self.reporter, Box::new(HumanReporter::new()));
+        reporter.summary(self, status);
+        self.reporter = reporter;
+    }
+
+    pub fn set_block_on(&mut self, level: BlockLevel) {
+        self.block_on = level;
+    }
+
+    pub fn block_on(&self) -> BlockLevel {
+        self.block_on
+    }
+
+    pub fn set_allow_fix(&mut self, allow: bool) {
+        self.allow_fix = allow;
+    }
+
+    pub fn allow_fix(&self) -> bool {
+        self.allow_fix
+    }
+
+    pub fn invalidate_staged_files(&mut self) {
+        self.staged_files_cache = None;
+        self.current_file_content_cache = None;
+        self.current_file_diff_cache = None;
+    }
+
     pub fn set_var(&mut self, name: String, value: String) {
         self.vars.insert(name, value);
     }
 
+    /// Staged files matching `pattern`, filtered from the cached list of all staged files
     pub fn staged_files(&mut self, pattern: &str) -> Result<Vec<String>> {
-        if pattern == "*" {
-            if let Some(files) = &self.staged_files_cache {
-                return Ok(files.clone());
+        let files = match &self.staged_files_cache {
+            Some(files) => files.clone(),
+            None => {
+                let files = githook_git::get_staged_files("*")?;
+                self.staged_files_cache = Some(files.clone());
+                files
             }
-            let files = githook_git::get_staged_files(pattern)?;
-            self.staged_files_cache = Some(files.clone());
+        };
+
+        if pattern == "*" {
             return Ok(files);
         }
+        githook_git::filter_files(files, pattern)
+    }
 
-        githook_git::get_staged_files(pattern)
+    #[cfg(test)]
+    pub(crate) fn set_staged_files(&mut self, files: Vec<String>) {
+        self.staged_files_cache = Some(files);
     }
 
     pub fn get_var(&self, name: &str) -> Option<&str> {
@@ -417,11 +752,11 @@ impl ExecutionContext {
         !self.warnings.is_empty()
     }
 
-    pub fn warnings(&self) -> &HashMap<String, Vec<String>> {
+    pub fn warnings(&self) -> &[(String, Vec<String>)] {
         &self.warnings
     }
 
-    pub fn checks_passed(&self) -> &[String] {
+    pub fn checks_passed(&self) -> &[CheckResult] {
         &self.checks_passed
     }
 
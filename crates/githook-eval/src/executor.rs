@@ -1,14 +1,18 @@
-use crate::context::ExecutionContext;
-use crate::conditions::evaluate_block_condition;
+use crate::context::{BlockLevel, CheckResult, ExecutionContext};
+use crate::conditions::{evaluate_block_condition, get_cached_regex};
+use crate::reporter::RecordingReporter;
 use githook_syntax::{
-    Argument, ContentCheck, ContentScope, MessageCheck, RuleSeverity, Statement,
-    MatchSubject, MatchArm, MatchPattern
+    Argument, ContentCheck, ContentScope, Diagnostic, FileSource, MessageCheck, RuleSeverity, Statement,
+    MatchSubject, MatchArm, MatchPattern, parse_spanned, tokenize_with_spans
 };
 use anyhow::{Result, bail, Context as AnyhowContext};
 use colored::*;
 use regex::Regex;
+use std::path::Path;
 use std::process::Command;
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::OnceLock;
 
 #[derive(Debug, PartialEq)]
@@ -16,6 +20,14 @@ pub enum ExecutionStatus {
     Ok,
     Warn,
     Block,
+    /// Blocked only by warnings, from non-critical groups under `--block-on` or `--max-warnings`
+    BlockOnWarnings,
+}
+
+impl ExecutionStatus {
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, ExecutionStatus::Block | ExecutionStatus::BlockOnWarnings)
+    }
 }
 
 static GLOB_CACHE: OnceLock<std::sync::Mutex<lru::LruCache<String, glob::Pattern>>> = OnceLock::new();
@@ -49,8 +61,11 @@ fn substitute_placeholders<'a>(input: &'a str, context: &ExecutionContext) -> Co
     let mut out = input.to_string();
     let mut changed = false;
 
-    let re = regex::Regex::new(r"\{([a-z]+):([a-zA-Z0-9_]+)(\|[a-z0-9_:]+)*\}")
-        .expect("Valid regex pattern for placeholder matching");
+    static PLACEHOLDER_RE: OnceLock<Regex> = OnceLock::new();
+    let re = PLACEHOLDER_RE.get_or_init(|| {
+        Regex::new(r"\{([a-z]+):([a-zA-Z0-9_]+)(\|[a-z0-9_:.\-]+)*\}")
+            .expect("Valid regex pattern for placeholder matching")
+    });
     
     for cap in re.captures_iter(input) {
         let full_match = &cap[0];
@@ -95,9 +110,32 @@ fn apply_filter(value: &str, filter: &str) -> String {
         value.trim().to_string()
     } else if filter == "len" {
         value.len().to_string()
+    } else if filter == "char_count" {
+        value.chars().count().to_string()
+    } else if filter == "word_count" {
+        value.split_whitespace().count().to_string()
     } else if filter.starts_with("truncate:") {
         let n: usize = filter.strip_prefix("truncate:").and_then(|s| s.parse().ok()).unwrap_or(10);
         value.chars().take(n).collect()
+    } else if let Some(args) = filter.strip_prefix("pad_start:") {
+        let (width, fill) = parse_pad_args(args);
+        let padding = width.saturating_sub(value.chars().count());
+        format!("{}{}", fill.to_string().repeat(padding), value)
+    } else if let Some(args) = filter.strip_prefix("pad_end:") {
+        let (width, fill) = parse_pad_args(args);
+        let padding = width.saturating_sub(value.chars().count());
+        format!("{}{}", value, fill.to_string().repeat(padding))
+    } else if let Some(spaces) = filter.strip_prefix("indent:") {
+        let pad = " ".repeat(spaces.parse().unwrap_or(0));
+        map_lines(value, |line| if line.trim().is_empty() { line.to_string() } else { format!("{}{}", pad, line) })
+    } else if filter == "dedent" {
+        let common = value
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        map_lines(value, |line| line.get(common..).unwrap_or("").to_string())
     } else if filter.starts_with("replace:") {
         let parts: Vec<&str> = filter.strip_prefix("replace:").unwrap_or("").split(':').collect();
         if parts.len() >= 2 {
@@ -110,6 +148,21 @@ fn apply_filter(value: &str, filter: &str) -> String {
     }
 }
 
+/// Applies `f` to every line, keeping a trailing newline
+fn map_lines(value: &str, f: impl Fn(&str) -> String) -> String {
+    let mut out = value.lines().map(f).collect::<Vec<_>>().join("\n");
+    if value.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Parses `width[:fill]` for the pad filters, filling with spaces by default
+fn parse_pad_args(args: &str) -> (usize, char) {
+    let (width, fill) = args.split_once(':').unwrap_or((args, " "));
+    (width.parse().unwrap_or(0), fill.chars().next().unwrap_or(' '))
+}
+
 fn parse_command(cmd: &str) -> Result<(String, Vec<String>)> {
     let args = shell_words::split(cmd)
         .map_err(|e| anyhow::anyhow!("Failed to parse command '{}': {}", cmd, e))?;
@@ -131,91 +184,201 @@ pub fn execute_with_filters(
     allowed_groups: Option<Vec<String>>,
     skipped_groups: Option<Vec<String>>,
 ) -> Result<ExecutionStatus> {
-    let mut context = ExecutionContext::new_with_filters(allowed_groups, skipped_groups);
+    let context = ExecutionContext::new_with_filters(allowed_groups, skipped_groups);
+    execute_with_context(statements, hook_args, context)
+}
 
-    for statement in &statements {
-        if !execute_statement(statement, &mut context, hook_args)? {
-            print_summary(&context);
-            return Ok(ExecutionStatus::Block);
-        }
-    }
+pub fn execute_with_context(
+    statements: Vec<Statement>,
+    hook_args: &[String],
+    mut context: ExecutionContext,
+) -> Result<ExecutionStatus> {
+    execute_statements(&statements, hook_args, &mut context)
+}
 
-    print_summary(&context);
+/// Outcome of [`Executor::run_source`]
+#[derive(Debug)]
+pub struct RunReport {
+    /// Checks that passed, with the span of the group that ran them
+    pub checks: Vec<CheckResult>,
+    /// Messages of the warnings that were raised, deduplicated
+    pub warnings: Vec<String>,
+    /// Messages of everything that blocked the hook
+    pub blocks: Vec<String>,
+    pub result: ExecutionStatus,
+}
 
-    if context.has_warnings() {
-        Ok(ExecutionStatus::Warn)
-    } else {
-        Ok(ExecutionStatus::Ok)
+/// Runs hooks from source strings without printing anything, for embedding
+/// githook in other tools
+pub struct Executor;
+
+impl Executor {
+    /// Parse and run a hook with default settings and no hook arguments.
+    /// Syntax errors are returned as rendered diagnostics.
+    pub fn run_source(source: &str) -> Result<RunReport> {
+        Self::run_source_with(source, &[], ExecutionContext::new())
     }
-}
 
-fn print_summary(ctx: &ExecutionContext) {
-    println!("\n{}", "═".repeat(50));
+    /// Like [`Executor::run_source`], with hook arguments and a configured context
+    pub fn run_source_with(source: &str, hook_args: &[String], mut context: ExecutionContext) -> Result<RunReport> {
+        let tokens = tokenize_with_spans(source)
+            .map_err(|e| anyhow::anyhow!("{}", Diagnostic::new_lex(source, e).with_color(false)))?;
+        let statements = parse_spanned(tokens)
+            .map_err(|e| anyhow::anyhow!("{}", Diagnostic::new_parse(source, e).with_color(false)))?;
 
-    let checks = ctx.checks_run();
+        let blocks = Rc::new(RefCell::new(Vec::new()));
+        context.set_reporter(Box::new(RecordingReporter::new(Rc::clone(&blocks))));
 
-    if checks == 0 {
-        println!("o {} checks completed", checks);
-    } else {
-        println!("o {} check{} completed", checks, if checks == 1 { "" } else { "s" });
-    }
+        let result = execute_statements(&statements, hook_args, &mut context)?;
 
-    if !ctx.checks_passed().is_empty() {
-        println!("\no Passed checks:");
-        for check in ctx.checks_passed() {
-            println!("  - {}", check);
-        }
+        Ok(RunReport {
+            checks: context.checks_passed().to_vec(),
+            warnings: context.warnings().iter().map(|(message, _)| message.clone()).collect(),
+            blocks: blocks.take(),
+            result,
+        })
     }
+}
 
-    if ctx.has_warnings() {
-        println!("\n{} Warnings:", "!".yellow());
-        for (warning, locations) in ctx.warnings() {
-            println!("  - {}", warning.yellow());
-            for loc in locations {
-                println!("    in {}", loc.dimmed());
+fn execute_statements(
+    statements: &[Statement],
+    hook_args: &[String],
+    context: &mut ExecutionContext,
+) -> Result<ExecutionStatus> {
+    let mut blocked = false;
+    let mut critical = false;
+
+    for statement in statements {
+        let warning_blocks = context.warning_blocks();
+        context.set_check_span(Some(statement.span()));
+        if !execute_statement(statement, context, hook_args)? {
+            blocked = true;
+            critical |= context.warning_blocks() == warning_blocks;
+            if context.fail_fast() {
+                break;
             }
         }
     }
 
-    println!("{}", "═".repeat(50));
+    if !blocked && context.exceeds_max_warnings() {
+        let message = format!(
+            "Too many warnings: {} (max {})",
+            context.warning_count(),
+            context.max_warnings().unwrap_or_default()
+        );
+        context.reporter().block(&message);
+        blocked = true;
+    }
+
+    let status = if critical {
+        ExecutionStatus::Block
+    } else if blocked {
+        ExecutionStatus::BlockOnWarnings
+    } else if context.has_warnings() {
+        ExecutionStatus::Warn
+    } else {
+        ExecutionStatus::Ok
+    };
+
+    context.report_summary(&status);
+    Ok(status)
 }
 
-fn execute_run(cmd: &str, context: &mut ExecutionContext) -> Result<bool> {
+fn execute_run(
+    cmd: &str,
+    cwd: Option<&str>,
+    env: &[(String, String)],
+    fixes: bool,
+    retries: usize,
+    backoff: f64,
+    context: &mut ExecutionContext,
+) -> Result<bool> {
     let rendered_cmd = substitute_placeholders(cmd, context);
     
     if !context.is_command_allowed(&rendered_cmd) {
-        println!("  {} Command '{}' is not in allow list", "x".red(), rendered_cmd.red());
+        context.reporter().block(&format!("Command '{}' is not in allow list", rendered_cmd));
         return Ok(false);
     }
+
+    if fixes && !context.allow_fix() {
+        context.reporter().note(&format!("Skipping fix '{}' (run with --fix to apply)", rendered_cmd));
+        return Ok(true);
+    }
     context.check_run();
 
+    // Only files the fixer rewrites are staged again, so unstaged hunks of other files stay unstaged
+    let snapshot = if fixes {
+        githook_git::worktree_snapshot(&context.staged_files("*")?)
+    } else {
+        Vec::new()
+    };
+
     let (program, args) = match parse_command(&rendered_cmd) {
         Ok(parsed) => parsed,
         Err(e) => {
-            println!("  {} Failed to parse command: {}", "x".red(), e);
+            context.reporter().block(&format!("Failed to parse command: {}", e));
             return Ok(false);
         }
     };
     
     if program.is_empty() {
-        println!("  {} Empty command", "x".red());
+        context.reporter().block("Empty command");
+        return Ok(false);
+    }
+
+    let dir = cwd.map(|dir| substitute_placeholders(dir, context).into_owned());
+    if let Some(dir) = &dir
+        && !Path::new(dir).is_dir()
+    {
+        context.reporter().block(&format!("Directory '{}' for '{}' does not exist", dir, rendered_cmd));
         return Ok(false);
     }
 
-    let output = Command::new(&program)
-        .args(&args)
-        .output()?;
+    let env: Vec<(String, String)> = env
+        .iter()
+        .map(|(key, value)| (key.clone(), substitute_placeholders(value, context).into_owned()))
+        .collect();
+
+    let run_once = || {
+        let mut command = Command::new(&program);
+        command.args(&args).envs(env.iter().map(|(key, value)| (key, value)));
+        if let Some(dir) = &dir {
+            command.current_dir(dir);
+        }
+        command.output()
+    };
+
+    let mut output = run_once()?;
+
+    for attempt in 1..=retries {
+        if output.status.success() {
+            break;
+        }
+        context.reporter().note(&format!("Retrying '{}' ({}/{})", rendered_cmd, attempt, retries));
+        if let Ok(delay) = std::time::Duration::try_from_secs_f64(backoff * attempt as f64) {
+            std::thread::sleep(delay);
+        }
+        output = run_once()?;
+    }
 
     if !output.status.success() {
-        println!("  {} Command failed: {}", "x".red(), rendered_cmd.red());
+        context.reporter().block(&format!("Command failed: {}", rendered_cmd));
         if !output.stderr.is_empty() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             for line in stderr.lines() {
-                println!("    {}", line.dimmed());
+                context.reporter().detail(line);
             }
         }
         return Ok(false);
     }
+
+    if fixes {
+        githook_git::restage_files(&githook_git::changed_since_snapshot(&snapshot))?;
+        context.invalidate_staged_files();
+        context.check_passed(format!("Fix: {}", rendered_cmd));
+        return Ok(true);
+    }
+
     context.check_passed(format!("Command: {}", rendered_cmd));
     Ok(true)
 }
@@ -256,19 +419,37 @@ fn execute_group(definition: &githook_syntax::GroupDefinition, context: &mut Exe
         None => "GROUP",
     };
     
-    println!("\n{} [{}]", format!("- {}", definition.name).cyan().bold(), severity_str.yellow());
+    context.reporter().group(&definition.name, severity_str);
     
+    let checks_before = context.checks_run();
+    let outer_span = context.set_check_span(Some(definition.span));
     let mut all_passed = true;
     for stmt in &definition.body {
         if !execute_statement(stmt, context, hook_args)? {
             all_passed = false;
         }
     }
+    context.set_check_span(outer_span);
+
+    if context.checks_run() == checks_before {
+        let msg = format!("Group '{}' ran no checks", definition.name);
+        if context.warn_empty_groups() {
+            context.warn(msg);
+        } else {
+            context.reporter().note(&msg);
+        }
+    }
     
-    if all_passed {
-        println!("{} Group '{}' passed", "o".green().bold(), definition.name.green());
-    } else {
-        println!("{} Group '{}' failed", "x".red().bold(), definition.name.red());
+    context.reporter().group_result(&definition.name, all_passed, &definition.span);
+
+    let level = BlockLevel::of(definition.severity.as_ref());
+    if !all_passed && level < context.block_on() {
+        context.warn(format!("Group '{}' failed ({})", definition.name, severity_str.to_lowercase()));
+        return Ok(true);
+    }
+
+    if !all_passed && level < BlockLevel::Critical {
+        context.record_warning_block();
     }
     
     Ok(all_passed)
@@ -279,8 +460,17 @@ fn execute_let_string_list(name: String, items: Vec<String>, context: &mut Execu
     Ok(true)
 }
 
-fn execute_block(msg: &str) -> Result<bool> {
-    println!("  {} {}", "x".red().bold(), msg.red());
+fn execute_assign(name: &str, items: Vec<String>, context: &mut ExecutionContext) -> Result<bool> {
+    if context.get_string_list(name).is_none() {
+        bail!("Cannot assign to undeclared list '{}' (declare it first with 'let {} = [...]')", name, name);
+    }
+    context.set_string_list(name.to_string(), items);
+    Ok(true)
+}
+
+fn execute_block(msg: &str, context: &mut ExecutionContext) -> Result<bool> {
+    let message = substitute_placeholders(msg, context).into_owned();
+    context.reporter().block(&message);
     Ok(false)
 }
 
@@ -294,7 +484,7 @@ fn execute_foreach_string_list(
     let items = match context.get_string_list(list) {
         Some(xs) => xs.to_vec(),
         None => {
-            println!("  {} unknown string list '{}'", "x".red(), list.red());
+            context.reporter().block(&format!("unknown string list '{}'", list));
             return Ok(false);
         }
     };
@@ -328,7 +518,7 @@ fn execute_foreach_array(
                 context.get_var(id).map(|s| s.to_string()).unwrap_or_else(|| id.clone())
             },
             githook_syntax::Argument::Array(_, _) => {
-                println!("  {} nested arrays not supported in foreach", "x".red());
+                context.reporter().block("nested arrays not supported in foreach");
                 return Ok(false);
             }
         };
@@ -350,13 +540,21 @@ fn execute_foreach_array(
 
 fn execute_foreach_staged_files(
     var: &str,
+    source: &FileSource,
     pattern: &str,
     where_cond: &Option<githook_syntax::BlockCondition>,
     body: &[Statement],
     context: &mut ExecutionContext,
     hook_args: &[String]
 ) -> Result<bool> {
-    let files = context.staged_files(pattern)?;
+    let files = match source {
+        FileSource::Staged => context.staged_files(pattern)?,
+        FileSource::Added => githook_git::get_added_files(pattern)?,
+        FileSource::Deleted => githook_git::get_deleted_files(pattern)?,
+        FileSource::ChangedSince(git_ref) => githook_git::get_files_changed_since(git_ref, pattern)?,
+        FileSource::CommitFiles => githook_git::get_commit_files(pattern)?,
+        FileSource::ConflictFiles => githook_git::get_conflict_files(pattern)?,
+    };
 
     if files.is_empty() {
         return Ok(true);
@@ -386,14 +584,20 @@ fn execute_foreach_staged_files(
     }
 
     context.unset_var(var);
-    context.check_passed(format!("foreach {} in staged_files matching '{}'", var, pattern));
+    context.check_passed(format!("foreach {} in {} matching '{}'", var, source, pattern));
     Ok(true)
 }
 
-fn execute_parallel(commands: &[String], context: &mut ExecutionContext) -> Result<bool> {
+fn execute_parallel(commands: &[String], pattern: Option<&str>, context: &mut ExecutionContext) -> Result<bool> {
     use std::sync::{Arc, Mutex};
     use std::thread;
 
+    if let Some(pattern) = pattern
+        && context.staged_files(pattern)?.is_empty()
+    {
+        return Ok(true);
+    }
+
     context.check_run();
 
     let results = Arc::new(Mutex::new(Vec::new()));
@@ -450,12 +654,12 @@ fn execute_parallel(commands: &[String], context: &mut ExecutionContext) -> Resu
 
     if !joins_ok || results.len() != commands.len() {
         all_passed = false;
-        println!("  {} Parallel execution failed", "x".red());
+        context.reporter().block("Parallel execution failed");
     }
 
     for (cmd, success, output) in results.iter() {
         if !*success {
-            println!("  {} {}", "x".red(), cmd.red());
+            context.reporter().block(cmd);
             all_passed = false;
 
             if let Ok(output) = output
@@ -463,7 +667,7 @@ fn execute_parallel(commands: &[String], context: &mut ExecutionContext) -> Resu
             {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 for line in stderr.lines() {
-                    println!("    {}", line.dimmed());
+                    context.reporter().detail(line);
                 }
             }
         }
@@ -495,14 +699,14 @@ fn execute_staged_content_validation(
 
     let mut content = String::new();
     for file in &files {
-        let file_content = githook_git::get_staged_file_content_from_index(file)?;
+        let file_content = context.file_content(file)?;
         content.push_str(&file_content);
         content.push('\n');
     }
 
     let result = match check {
         ContentCheck::Match(pattern, _) => {
-            let regex = Regex::new(pattern)?;
+            let regex = get_cached_regex(pattern)?;
             regex.is_match(&content)
         }
         ContentCheck::Contain(text, _) => content.contains(text),
@@ -516,7 +720,7 @@ fn execute_staged_content_validation(
             ContentCheck::Contain(t, _) => format!("contain '{}'", t),
         };
         let must_str = if must { "must" } else { "must not" };
-        println!("  {} staged_content {} {}", "x".red(), must_str.red(), check_desc.red());
+        context.reporter().block(&format!("staged_content {} {}", must_str, check_desc));
         return Ok(false);
     }
 
@@ -564,6 +768,22 @@ fn execute_allow_command(cmd: &str, context: &mut ExecutionContext) -> Result<bo
     Ok(true)
 }
 
+fn execute_require_clean(context: &mut ExecutionContext) -> Result<bool> {
+    context.check_run();
+
+    let dirty = githook_git::get_dirty_files()?;
+    if dirty.is_empty() {
+        context.check_passed("Working tree is clean".to_string());
+        return Ok(true);
+    }
+
+    context.reporter().block("Working tree has unstaged or untracked changes");
+    for file in &dirty {
+        context.reporter().detail(file);
+    }
+    Ok(false)
+}
+
 fn execute_staged_files(pattern: &str, body: &[Statement], context: &mut ExecutionContext, hook_args: &[String]) -> Result<bool> {
     let files = context.staged_files(pattern)?;
 
@@ -617,13 +837,13 @@ fn execute_file_rule(pattern: &str, must_be_staged: bool, context: &mut Executio
 
     if must_be_staged {
         if !staged {
-            println!("  {} File matching {} must be staged!", "x".red(), pattern.red());
+            context.reporter().block(&format!("File matching {} must be staged!", pattern));
             return Ok(false);
         }
         context.check_passed(format!("File '{}' must be staged", pattern));
     } else {
         if staged {
-            println!("  {} File matching {} must not be staged!", "x".red(), pattern.red());
+            context.reporter().block(&format!("File matching {} must not be staged!", pattern));
             return Ok(false);
         }
         context.check_passed(format!("File '{}' must not be staged", pattern));
@@ -649,7 +869,7 @@ fn execute_content_validation(
     let content = match scope {
         ContentScope::Content(_) => {
             if let Some(file) = context.current_file() {
-                githook_git::get_staged_file_content_from_index(file)?
+                context.file_content(file)?
             } else {
                 githook_git::get_staged_file_content(file_pattern)?
             }
@@ -664,7 +884,7 @@ fn execute_content_validation(
 
     let result = match check {
         ContentCheck::Match(pattern, _) => {
-            let regex = Regex::new(pattern)?;
+            let regex = get_cached_regex(pattern)?;
             regex.is_match(&content)
         }
         ContentCheck::Contain(text, _) => content.contains(text),
@@ -678,7 +898,7 @@ fn execute_content_validation(
             ContentCheck::Contain(t, _) => format!("contain '{}'", t),
         };
         let must_str = if must { "must" } else { "must not" };
-        println!("  {} {} {} {}", "x".red(), scope_name.red(), must_str.red(), check_desc.red());
+        context.reporter().block(&format!("{} {} {}", scope_name, must_str, check_desc));
         return Ok(false);
     }
 
@@ -721,7 +941,8 @@ fn execute_conditional_rule(
     if result {
         match severity {
             RuleSeverity::Warn(_) => {
-                println!("  {} {}", "-".yellow(), message_str.yellow());
+                // Only counted once the prompt is accepted, so declined warnings don't reach the summary
+                context.report_warning(message_str);
 
                 if let Some(prompt) = interactive {
                     if atty::is(atty::Stream::Stdin) {
@@ -735,24 +956,24 @@ fn execute_conditional_rule(
                             Ok(_) => {
                                 let answer = input.trim().to_lowercase();
                                 if answer != "y" && answer != "yes" {
-                                    println!("  {} Aborted by user", "x".red());
+                                    context.reporter().block("Aborted by user");
                                     return Ok(false);
                                 }
                             }
                             Err(_) => {
-                                println!("  {} (Skipping interactive prompt - no TTY)", "!".yellow());
+                                context.reporter().note("(Skipping interactive prompt - no TTY)");
                             }
                         }
                     } else {
-                        println!("  {} (Skipping interactive prompt - running in non-interactive mode)", "!".yellow());
+                        context.reporter().note("(Skipping interactive prompt - running in non-interactive mode)");
                     }
                 }
 
-                context.warn(message_str.to_string());
+                context.record_warning(message_str.to_string());
                 return Ok(true);
             }
             RuleSeverity::Block(_) => {
-                println!("  {} {}", "x".red(), message_str.red());
+                context.reporter().block(message_str);
                 context.fail_check(message_str.to_string());
                 return Ok(false);
             }
@@ -768,7 +989,7 @@ fn execute_message_validation(must: bool, check: &MessageCheck, context: &mut Ex
     let msg = githook_git::get_commit_message_from_hook_args(hook_args)?;
     let result = match check {
         MessageCheck::Match(pattern, _) => {
-            let regex = Regex::new(pattern)?;
+            let regex = get_cached_regex(pattern)?;
             regex.is_match(&msg)
         }
         MessageCheck::Contain(text, _) => msg.contains(text),
@@ -782,8 +1003,8 @@ fn execute_message_validation(must: bool, check: &MessageCheck, context: &mut Ex
             MessageCheck::Contain(t, _) => format!("contain '{}'", t),
         };
         let must_str = if must { "must" } else { "must not" };
-        println!("  {} Commit message {} {}", "x".red(), must_str.red(), check_desc.red());
-        println!("    Message: {}", msg.dimmed());
+        context.reporter().block(&format!("Commit message {} {}", must_str, check_desc));
+        context.reporter().detail(&format!("Message: {}", msg));
         return Ok(false);
     }
 
@@ -812,6 +1033,10 @@ fn execute_macro_definition(name: String, params: Vec<String>, body: Vec<Stateme
 
 //
 pub fn execute_macro_call(namespace: Option<&str>, name: &str, args: &[Argument], context: &mut ExecutionContext, hook_args: &[String]) -> Result<bool> {
+    if namespace.is_none() && name == "deprecated" && context.get_macro(name).is_none() {
+        return execute_deprecated(args, context);
+    }
+
     let lookup_name = if let Some(ns) = namespace {
         format!("{}:{}", ns, name)
     } else {
@@ -839,14 +1064,41 @@ pub fn execute_macro_call(namespace: Option<&str>, name: &str, args: &[Argument]
         context.set_param(param.clone(), value);
     }
     
-    for statement in &snippet.body {
+    context.enter_macro(lookup_name)?;
+    let result = execute_macro_body(&snippet.body, context, hook_args);
+    context.leave_macro();
+    context.clear_params();
+    result
+}
+
+fn execute_macro_body(body: &[Statement], context: &mut ExecutionContext, hook_args: &[String]) -> Result<bool> {
+    for statement in body {
         if !execute_statement(statement, context, hook_args)? {
-            context.clear_params();
             return Ok(false);
         }
     }
+    Ok(true)
+}
+
+fn execute_deprecated(args: &[Argument], context: &mut ExecutionContext) -> Result<bool> {
+    let macro_name = context.current_macro()
+        .ok_or_else(|| anyhow::anyhow!("@deprecated can only be used inside a macro body"))?
+        .to_string();
+
+    let note = match args {
+        [] => None,
+        [Argument::String(s, _)] => Some(substitute_placeholders(s, context).into_owned()),
+        _ => bail!("@deprecated expects at most one string argument"),
+    };
+
+    if context.mark_deprecation_reported(&macro_name) {
+        let message = match note {
+            Some(note) => format!("@{} is deprecated: {}", macro_name, note),
+            None => format!("@{} is deprecated", macro_name),
+        };
+        context.warn(message);
+    }
 
-    context.clear_params();
     Ok(true)
 }
 
@@ -902,7 +1154,25 @@ fn execute_import_statement(
         cwd.join(path).to_string_lossy().to_string()
     };
 
-    let content = std::fs::read_to_string(&file_path)?;
+    let canonical = std::fs::canonicalize(&file_path)
+        .with_context(|| format!("Failed to resolve import '{}'", path))?;
+    let canonical = canonical.to_string_lossy().to_string();
+
+    context.enter_import(canonical.clone())?;
+    let first_import = context.mark_imported(canonical);
+    let result = execute_imported_file(&file_path, alias, first_import, context, hook_args);
+    context.leave_import();
+    result
+}
+
+fn execute_imported_file(
+    file_path: &str,
+    alias: Option<&str>,
+    first_import: bool,
+    context: &mut ExecutionContext,
+    hook_args: &[String],
+) -> Result<bool> {
+    let content = std::fs::read_to_string(file_path)?;
     let tokens = githook_syntax::tokenize_with_spans(&content)?;
     let statements = githook_syntax::parse_spanned(tokens)
         .map_err(|e| anyhow::anyhow!("{}", e))?;
@@ -923,6 +1193,11 @@ fn execute_import_statement(
             statement
         };
 
+        // A file that was already imported only re-registers its macros under the new alias
+        if !first_import && !matches!(scoped_statement, githook_syntax::Statement::MacroDefinition { .. }) {
+            continue;
+        }
+
         if !execute_statement(&scoped_statement, context, hook_args)? {
             return Ok(false);
         }
@@ -958,23 +1233,28 @@ pub fn execute_statement(
     hook_args: &[String],
 ) -> Result<bool> {
     match statement {
-        Statement::Run(cmd, _) => execute_run(cmd, context),
+        Statement::Run { command, cwd, env, fixes, retries, backoff, .. } => {
+            execute_run(command, cwd.as_deref(), env, *fixes, *retries, *backoff, context)
+        }
         Statement::BoolLiteral(value, _) => execute_bool_literal(*value, context),
+        Statement::Comment { .. } => Ok(true),
         Statement::Group { definition, span: _ } => execute_group(definition, context, hook_args),
         Statement::LetStringList { name, items, .. } => execute_let_string_list(name.clone(), items.clone(), context),
-        Statement::Block(msg, _) => execute_block(msg),
+        Statement::Assign { name, items, .. } => execute_assign(name, items.clone(), context),
+        Statement::Block(msg, _) => execute_block(msg, context),
         Statement::ForEachStringList { var, list, body, .. } => execute_foreach_string_list(var, list, body, context, hook_args),
         Statement::ForEachArray { var, items, body, .. } => execute_foreach_array(var, items, body, context, hook_args),
-        Statement::ForEachStagedFiles { var, pattern, where_cond, body, .. } => {
-            execute_foreach_staged_files(var, pattern, where_cond, body, context, hook_args)
+        Statement::ForEachStagedFiles { var, source, pattern, where_cond, body, .. } => {
+            execute_foreach_staged_files(var, source, pattern, where_cond, body, context, hook_args)
         }
-        Statement::Parallel { commands, .. } => execute_parallel(commands, context),
+        Statement::Parallel { commands, pattern, .. } => execute_parallel(commands, pattern.as_deref(), context),
         Statement::StagedFiles { pattern, body, .. } => execute_staged_files(pattern, body, context, hook_args),
         Statement::StagedContentValidation { must, check, pattern, .. } => {
             execute_staged_content_validation(*must, check, pattern, context)
         }
         Statement::StagedContentForeach { pattern, body, .. } => execute_staged_content_foreach(pattern, body, context, hook_args),
         Statement::AllowCommand(cmd, _) => execute_allow_command(cmd, context),
+        Statement::RequireClean(_) => execute_require_clean(context),
         Statement::AllFiles { pattern, body, .. } => execute_all_files(pattern, body, context, hook_args),
         Statement::FileRule { pattern, must_be_staged, .. } => execute_file_rule(pattern, *must_be_staged, context),
         Statement::ContentValidation { scope, must, check, pattern, .. } => {
@@ -1004,6 +1284,8 @@ pub fn execute_statement(
     }
 }
 
+const MATCHED_VAR: &str = "matched";
+
 fn execute_match_statement(
     subject: &MatchSubject,
     arms: &[MatchArm],
@@ -1024,20 +1306,46 @@ fn execute_match_statement(
                 .with_context(|| format!("Failed to read file: {}", file))?
         }
         MatchSubject::Diff(_) => {
-            let file = context.current_file.as_ref().ok_or_else(|| {
+            let file = context.current_file.clone().ok_or_else(|| {
                 anyhow::anyhow!("'match diff' requires a current file (use inside foreach staged_files ...)")
             })?;
-            githook_git::get_staged_file_content_from_index(file)
+            context.current_file_diff()
                 .with_context(|| format!("Failed to get diff for: {}", file))?
         }
+        MatchSubject::Branch(_) => githook_git::get_branch_name()?,
+    };
+
+    let Some(arm) = find_match_arm(subject, &value_to_match, arms)? else {
+        return Ok(true);
     };
 
+    // The subject is available as `{matched}` inside the arm, restoring any outer match
+    let outer = context.get_var(MATCHED_VAR).map(str::to_string);
+    context.set_var(MATCHED_VAR.to_string(), value_to_match);
+
+    let mut passed = true;
+    for stmt in &arm.action {
+        if !execute_statement(stmt, context, hook_args)? {
+            passed = false;
+            break;
+        }
+    }
+
+    match outer {
+        Some(value) => context.set_var(MATCHED_VAR.to_string(), value),
+        None => context.unset_var(MATCHED_VAR),
+    }
+    Ok(passed)
+}
+
+/// The first arm whose pattern matches `value`, the subject's current value
+fn find_match_arm<'a>(subject: &MatchSubject, value: &str, arms: &'a [MatchArm]) -> Result<Option<&'a MatchArm>> {
     for arm in arms {
         let matches = match &arm.pattern {
             MatchPattern::Wildcard(pattern, _) => {
-                if matches!(subject, MatchSubject::File(_)) {
+                if matches!(subject, MatchSubject::File(_) | MatchSubject::Branch(_)) {
                     match get_cached_glob(pattern) {
-                        Ok(glob_pattern) => glob_pattern.matches(&value_to_match),
+                        Ok(glob_pattern) => glob_pattern.matches(value),
                         Err(e) => {
                             eprintln!("Warning: Invalid glob pattern '{}': {}", pattern, e);
                             false
@@ -1048,16 +1356,16 @@ fn execute_match_statement(
                 }
             }
             MatchPattern::Contains(text, _) => {
-                value_to_match.contains(text)
+                value.contains(text)
             }
             MatchPattern::Matches(regex_str, _) => {
-                let regex = regex::Regex::new(regex_str)
+                let regex = get_cached_regex(regex_str)
                     .with_context(|| format!("Invalid regex: {}", regex_str))?;
-                regex.is_match(&value_to_match)
+                regex.is_match(value)
             }
             MatchPattern::GreaterThan(threshold, _) => {
                 if matches!(subject, MatchSubject::File(_)) {
-                    let metadata = std::fs::metadata(&value_to_match)?;
+                    let metadata = std::fs::metadata(value)?;
                     (metadata.len() as f64) > *threshold
                 } else {
                     false
@@ -1065,7 +1373,7 @@ fn execute_match_statement(
             }
             MatchPattern::LessThan(threshold, _) => {
                 if matches!(subject, MatchSubject::File(_)) {
-                    let metadata = std::fs::metadata(&value_to_match)?;
+                    let metadata = std::fs::metadata(value)?;
                     (metadata.len() as f64) < *threshold
                 } else {
                     false
@@ -1074,14 +1382,317 @@ fn execute_match_statement(
         };
 
         if matches {
-            for stmt in &arm.action {
-                if !execute_statement(stmt, context, hook_args)? {
-                    return Ok(false);
-                }
+            return Ok(Some(arm));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::Reporter;
+    use githook_syntax::Span;
+
+    /// Captures warnings, blocks and failed groups instead of printing them
+    struct CapturingReporter {
+        events: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Reporter for CapturingReporter {
+        fn group(&mut self, _name: &str, _severity: &str) {}
+        fn group_result(&mut self, name: &str, passed: bool, _span: &Span) {
+            if !passed {
+                self.events.borrow_mut().push(format!("failed: {}", name));
             }
-            return Ok(true);
         }
+        fn check(&mut self, _description: &str) {}
+        fn warning(&mut self, message: &str) {
+            self.events.borrow_mut().push(format!("warning: {}", message));
+        }
+        fn block(&mut self, message: &str) {
+            self.events.borrow_mut().push(format!("block: {}", message));
+        }
+        fn detail(&mut self, _line: &str) {}
+        fn note(&mut self, _message: &str) {}
+        fn summary(&mut self, _context: &ExecutionContext, _status: &ExecutionStatus) {}
     }
 
-    Ok(true)
+    fn run_capturing(source: &str, configure: impl FnOnce(&mut ExecutionContext)) -> (ExecutionStatus, Vec<String>) {
+        let statements = parse_spanned(tokenize_with_spans(source).unwrap()).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut context = ExecutionContext::new();
+        configure(&mut context);
+        context.set_reporter(Box::new(CapturingReporter { events: Rc::clone(&events) }));
+
+        let status = execute_with_context(statements, &[], context).unwrap();
+        (status, events.take())
+    }
+
+    #[test]
+    fn warnings_and_blocks_go_through_the_reporter() {
+        let (status, events) = run_capturing("warn_if true message \"careful\"\nblock_if true message \"stop\"\n", |_| {});
+        assert_eq!(status, ExecutionStatus::Block);
+        assert_eq!(events, vec!["warning: careful", "block: stop"]);
+    }
+
+    #[test]
+    fn deduped_warning_is_reported_once() {
+        let source = "warn_if true message \"twice\"\nwarn_if true message \"twice\"\n";
+        let (status, events) = run_capturing(source, |context| context.set_dedupe_warnings(true));
+        assert_eq!(status, ExecutionStatus::Warn);
+        assert_eq!(events, vec!["warning: twice"]);
+
+        let (_, events) = run_capturing(source, |_| {});
+        assert_eq!(events.len(), 2);
+    }
+
+    fn run_statements(source: &str, context: &mut ExecutionContext) -> Result<ExecutionStatus> {
+        let statements = parse_spanned(tokenize_with_spans(source).unwrap()).unwrap();
+        context.set_reporter(Box::new(RecordingReporter::default()));
+        execute_statements(&statements, &[], context)
+    }
+
+    #[test]
+    fn reassignment_replaces_the_list() {
+        let mut context = ExecutionContext::new();
+        run_statements("let exts = [\"rs\"]\nexts = [\"toml\", \"md\"]\n", &mut context).unwrap();
+        assert_eq!(context.get_string_list("exts"), Some(&["toml".to_string(), "md".to_string()][..]));
+    }
+
+    #[test]
+    fn assigning_an_undeclared_list_errors() {
+        let mut context = ExecutionContext::new();
+        let err = run_statements("exts = [\"rs\"]\n", &mut context).unwrap_err();
+        assert!(err.to_string().contains("undeclared list 'exts'"));
+    }
+
+    /// Script for a command that fails until it has run `succeed_on` times
+    fn flaky_run(dir: &Path, succeed_on: usize, retries: usize) -> String {
+        format!(
+            "run \"sh -c 'n=$(cat count 2>/dev/null || echo 0); n=$((n + 1)); echo $n > count; [ $n -ge {} ]'\" in \"{}\" retry {}\n",
+            succeed_on,
+            dir.display(),
+            retries
+        )
+    }
+
+    #[test]
+    fn retry_reruns_a_flaky_command_until_it_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut context = ExecutionContext::new();
+        let status = run_statements(&flaky_run(dir.path(), 3, 3), &mut context).unwrap();
+        assert_eq!(status, ExecutionStatus::Ok);
+        assert_eq!(std::fs::read_to_string(dir.path().join("count")).unwrap().trim(), "3");
+    }
+
+    #[test]
+    fn retry_gives_up_after_the_last_attempt() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut context = ExecutionContext::new();
+        let status = run_statements(&flaky_run(dir.path(), 3, 1), &mut context).unwrap();
+        assert_eq!(status, ExecutionStatus::Block);
+        assert_eq!(std::fs::read_to_string(dir.path().join("count")).unwrap().trim(), "2");
+    }
+
+    #[test]
+    fn run_source_reports_checks_warnings_and_blocks() {
+        let report = Executor::run_source(
+            "group checks {\n  run \"true\"\n  warn_if true message \"careful\"\n}\nblock_if true message \"stop\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].description, "Command: true");
+        assert_eq!(report.warnings, vec!["careful"]);
+        assert_eq!(report.blocks, vec!["stop"]);
+        assert_eq!(report.result, ExecutionStatus::Block);
+    }
+
+    #[test]
+    fn run_source_returns_syntax_errors_as_diagnostics() {
+        let err = Executor::run_source("run 5\n").unwrap_err();
+        assert!(err.to_string().starts_with("error[E013]: "), "{}", err);
+        assert!(err.to_string().contains("--> line 1:"), "{}", err);
+    }
+
+    #[test]
+    fn check_results_carry_the_group_line() {
+        let report = Executor::run_source("run \"true\"\n\ngroup checks {\n  run \"true\"\n}\n").unwrap();
+
+        let lines: Vec<Option<usize>> = report.checks.iter().map(|check| check.span.map(|span| span.line)).collect();
+        assert_eq!(lines, vec![Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn self_recursive_macro_hits_the_depth_limit() {
+        let mut context = ExecutionContext::new();
+        context.set_max_macro_depth(8);
+        let err = run_statements("macro again {\n  @again\n}\n@again\n", &mut context).unwrap_err();
+        assert!(format!("{:#}", err).contains("Macro call depth limit (8) exceeded while calling @again"), "{:#}", err);
+    }
+
+    #[test]
+    fn two_file_import_cycle_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.ghook");
+        let b = dir.path().join("b.ghook");
+        std::fs::write(&a, format!("import \"{}\"\n", b.display())).unwrap();
+        std::fs::write(&b, format!("import \"{}\"\n", a.display())).unwrap();
+
+        let mut context = ExecutionContext::new();
+        let err = run_statements(&format!("import \"{}\"\n", a.display()), &mut context).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Import cycle detected"), "{}", message);
+        assert!(message.contains("a.ghook -> ") && message.contains("b.ghook -> "), "{}", message);
+    }
+
+    #[test]
+    fn importing_a_file_twice_runs_its_statements_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let helper = dir.path().join("helper.ghook");
+        std::fs::write(&helper, "let exts = [\"rs\"]\nmacro fmt {\n  run \"true\"\n}\n").unwrap();
+
+        let source = format!(
+            "import \"{path}\"\nexts = [\"toml\"]\nimport \"{path}\" as h\n@h:fmt\n",
+            path = helper.display()
+        );
+        let mut context = ExecutionContext::new();
+        let status = run_statements(&source, &mut context).unwrap();
+
+        assert_eq!(status, ExecutionStatus::Ok);
+        assert_eq!(context.get_string_list("exts"), Some(&["toml".to_string()][..]));
+    }
+
+    #[test]
+    fn failing_warning_group_does_not_block() {
+        let source = "group style {\n  severity: warning\n  block_if true message \"long line\"\n}\nrun \"true\"\n";
+        let (status, events) = run_capturing(source, |_| {});
+
+        assert_eq!(status, ExecutionStatus::Warn);
+        assert!(!status.is_blocked());
+        assert_eq!(
+            events,
+            vec!["block: long line", "failed: style", "warning: Group 'style' failed (warning)"]
+        );
+    }
+
+    #[test]
+    fn failing_groups_are_all_recorded_without_fail_fast() {
+        let source = "group first {\n  block_if true message \"one\"\n}\ngroup second {\n  block_if true message \"two\"\n}\n";
+
+        let (status, events) = run_capturing(source, |context| context.set_fail_fast(false));
+        assert_eq!(status, ExecutionStatus::Block);
+        assert_eq!(events, vec!["block: one", "failed: first", "block: two", "failed: second"]);
+
+        let (status, events) = run_capturing(source, |_| {});
+        assert_eq!(status, ExecutionStatus::Block);
+        assert_eq!(events, vec!["block: one", "failed: first"]);
+    }
+
+    #[test]
+    fn deprecated_macro_warns_once() {
+        let source = "macro old_lint {\n  @deprecated(\"use @lint\")\n  run \"true\"\n}\n@old_lint\n@old_lint\n";
+        let (status, events) = run_capturing(source, |_| {});
+
+        assert_eq!(status, ExecutionStatus::Warn);
+        assert_eq!(events, vec!["warning: @old_lint is deprecated: use @lint"]);
+    }
+
+    #[test]
+    fn env_not_equals_and_contains_treat_unset_variables_as_empty() {
+        const UNSET: &str = "GITHOOK_TEST_NEVER_SET_VARIABLE";
+        let path = std::env::var("PATH").unwrap();
+        let blocks = |condition: String| {
+            let (status, _) = run_capturing(&format!("block_if {condition} message \"hit\"\n"), |_| {});
+            status == ExecutionStatus::Block
+        };
+
+        assert!(blocks(format!("env \"PATH\" != \"{path}x\"")));
+        assert!(!blocks(format!("env \"PATH\" != \"{path}\"")));
+        assert!(blocks(format!("env \"{UNSET}\" != \"value\"")));
+        assert!(!blocks(format!("env \"{UNSET}\" != \"\"")));
+
+        assert!(blocks("env \"PATH\" contains \"/\"".to_string()));
+        assert!(!blocks("env \"PATH\" contains \"no such entry\"".to_string()));
+        assert!(!blocks(format!("env \"{UNSET}\" contains \"value\"")));
+        assert!(blocks(format!("env \"{UNSET}\" contains \"\"")));
+    }
+
+    #[test]
+    fn ignorecase_matches_uppercase_input_against_a_lowercase_pattern() {
+        let source = "match file {\n  matches \"^docs/.*\\.md$\" ignorecase -> warn_if true message \"{matched}\"\n}\n";
+        let (_, events) = run_capturing(source, |context| context.set_current_file(Some("DOCS/README.MD".to_string())));
+        assert_eq!(events, vec!["warning: DOCS/README.MD"]);
+
+        let (_, events) = run_capturing(&source.replace(" ignorecase", ""), |context| {
+            context.set_current_file(Some("DOCS/README.MD".to_string()))
+        });
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn parallel_block_is_skipped_without_matching_staged_files() {
+        let staged = || vec!["src/main.rs".to_string(), "README.md".to_string()];
+        let source = |pattern: &str| format!("parallel matching \"{pattern}\" {{\n  run \"false\"\n}}\n");
+
+        let (status, events) = run_capturing(&source("*.py"), |context| context.set_staged_files(staged()));
+        assert_eq!(status, ExecutionStatus::Ok);
+        assert!(events.is_empty());
+
+        let (status, events) = run_capturing(&source("src/*.rs"), |context| context.set_staged_files(staged()));
+        assert_eq!(status, ExecutionStatus::Block);
+        assert_eq!(events, vec!["block: false"]);
+    }
+
+    #[test]
+    fn run_executes_in_the_given_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = format!("run \"sh -c 'pwd > where'\" in \"{}\"\n", dir.path().display());
+        let status = run_statements(&source, &mut ExecutionContext::new()).unwrap();
+        assert_eq!(status, ExecutionStatus::Ok);
+
+        let printed = std::fs::read_to_string(dir.path().join("where")).unwrap();
+        assert_eq!(
+            std::fs::canonicalize(printed.trim()).unwrap(),
+            std::fs::canonicalize(dir.path()).unwrap()
+        );
+
+        let missing = format!("run \"true\" in \"{}\"\n", dir.path().join("missing").display());
+        let (status, events) = run_capturing(&missing, |_| {});
+        assert_eq!(status, ExecutionStatus::Block);
+        assert!(events[0].contains("missing' for 'true' does not exist"), "{:?}", events);
+    }
+
+    #[test]
+    fn run_passes_env_variables_to_the_child() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = format!(
+            "run \"sh -c 'echo $GREETING $TARGET > out'\" in \"{}\" env {{\n  \"GREETING\": \"hello\",\n  \"TARGET\": \"world\"\n}}\n",
+            dir.path().display()
+        );
+        let status = run_statements(&source, &mut ExecutionContext::new()).unwrap();
+        assert_eq!(status, ExecutionStatus::Ok);
+        assert_eq!(std::fs::read_to_string(dir.path().join("out")).unwrap(), "hello world\n");
+    }
+
+    #[test]
+    fn branch_arms_match_exact_names_and_globs() {
+        let statements = parse_spanned(tokenize_with_spans("match branch {\n  \"main\" -> run \"a\"\n  \"feature/*\" -> run \"b\"\n}\n").unwrap()).unwrap();
+        let Statement::Match { subject, arms, .. } = &statements[0] else {
+            panic!("expected a match statement");
+        };
+        assert!(matches!(subject, MatchSubject::Branch(_)));
+
+        let arm_index = |branch: &str| {
+            find_match_arm(subject, branch, arms)
+                .unwrap()
+                .map(|arm| arms.iter().position(|a| std::ptr::eq(a, arm)).unwrap())
+        };
+        assert_eq!(arm_index("main"), Some(0));
+        assert_eq!(arm_index("feature/login"), Some(1));
+        assert_eq!(arm_index("mainline"), None);
+        assert_eq!(arm_index("release/1.0"), None);
+    }
 }
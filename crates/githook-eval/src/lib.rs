@@ -2,7 +2,9 @@ mod context;
 mod executor;
 mod conditions;
 mod stdlib;
+pub mod reporter;
 pub mod package_resolver;
 
-pub use context::ExecutionContext;
-pub use executor::{execute, execute_with_filters, ExecutionStatus};
\ No newline at end of file
+pub use context::{BlockLevel, CheckResult, ExecutionContext};
+pub use reporter::{Reporter, HumanReporter, JsonReporter};
+pub use executor::{execute, execute_with_filters, execute_with_context, ExecutionStatus, Executor, RunReport};
\ No newline at end of file
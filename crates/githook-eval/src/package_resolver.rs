@@ -1,7 +1,108 @@
 use std::path::PathBuf;
 use std::fs;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
 use anyhow::{Result, bail, anyhow};
 
+pub const DEFAULT_PACKAGE_FETCH_TIMEOUT_SECS: u64 = 30;
+
+static PACKAGE_FETCH_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_PACKAGE_FETCH_TIMEOUT_SECS);
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static ALLOW_NETWORK: AtomicBool = AtomicBool::new(false);
+static ALLOWED_HOSTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Timeout applied to every remote package fetch
+pub fn set_package_fetch_timeout(secs: u64) {
+    PACKAGE_FETCH_TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
+
+pub fn package_fetch_timeout() -> Duration {
+    Duration::from_secs(PACKAGE_FETCH_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// In offline mode only installed or cached packages are used
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Remote package fetches are refused unless network access was enabled
+pub fn set_allow_network(allow: bool) {
+    ALLOW_NETWORK.store(allow, Ordering::Relaxed);
+}
+
+pub fn is_network_allowed() -> bool {
+    ALLOW_NETWORK.load(Ordering::Relaxed)
+}
+
+/// Hosts that package fetches may reach once network access is enabled
+pub fn set_allowed_hosts(hosts: Vec<String>) {
+    *ALLOWED_HOSTS.lock().expect("Allowed hosts lock should not be poisoned") = hosts;
+}
+
+pub fn check_host_allowed(url: &str) -> Result<()> {
+    let allowed = ALLOWED_HOSTS.lock().expect("Allowed hosts lock should not be poisoned");
+    check_host(url, is_network_allowed(), &allowed)
+}
+
+fn check_host(url: &str, allow_network: bool, allowed: &[String]) -> Result<()> {
+    if !allow_network {
+        bail!(
+            "Network access is disabled; refusing to fetch {}. Pass --allow-network and --allow-host <HOST> to enable it",
+            url
+        );
+    }
+
+    let parsed = reqwest::Url::parse(url)?;
+    let host = parsed.host_str().unwrap_or("");
+    if !allowed.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+        let allowed = if allowed.is_empty() { "none".to_string() } else { allowed.join(", ") };
+        bail!(
+            "Host '{}' is not in the allowed hosts ({}); refusing to fetch {}. Pass --allow-host {} to allow it",
+            host,
+            allowed,
+            url,
+            host
+        );
+    }
+    Ok(())
+}
+
+fn ensure_online(namespace: &str, name: &str) -> Result<()> {
+    if is_offline() {
+        bail!(
+            "Package @{}/{} is not installed and network access is disabled (--offline)",
+            namespace,
+            name
+        );
+    }
+    Ok(())
+}
+
+fn fetch_error(
+    err: reqwest::Error,
+    timeout: Duration,
+    namespace: &str,
+    name: &str,
+    url: &str,
+) -> anyhow::Error {
+    if err.is_timeout() {
+        anyhow!(
+            "Timed out after {}s fetching package @{}/{} from {}. Use --package-timeout to wait longer, or --offline to only use installed packages",
+            timeout.as_secs(),
+            namespace,
+            name,
+            url
+        )
+    } else {
+        anyhow!("Failed to fetch package @{}/{} from {}: {}", namespace, name, url, err)
+    }
+}
+
 fn local_packages_dir() -> Result<PathBuf> {
     let home = dirs::home_dir()
         .ok_or_else(|| anyhow!("Could not determine home directory"))?;
@@ -73,6 +174,28 @@ fn validate_repo_url(repo_url: &str) -> Result<()> {
     Ok(())
 }
 
+fn fetch_blocking(url: &str, timeout: Duration, namespace: &str, name: &str) -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()?;
+
+    let response = client.get(url).send()
+        .map_err(|e| fetch_error(e, timeout, namespace, name, url))?;
+
+    if !response.status().is_success() {
+        bail!(
+            "Failed to fetch package @{}/{} from {}: HTTP {}",
+            namespace,
+            name,
+            url,
+            response.status()
+        );
+    }
+
+    response.text()
+        .map_err(|e| fetch_error(e, timeout, namespace, name, url))
+}
+
 pub fn load_package(
     namespace: &str,
     name: &str,
@@ -84,6 +207,8 @@ pub fn load_package(
     }
 
     if namespace != "local" {
+        ensure_online(namespace, name)?;
+
         eprintln!("Package @{}/{} not found locally. Try installing it first.", namespace, name);
         eprintln!("Attempting to fetch from default repository...");
         
@@ -96,25 +221,10 @@ pub fn load_package(
             repo_url, namespace, name, name
         );
         
+        check_host_allowed(&url)?;
         eprintln!("Fetching from: {}", url);
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
-        
-        let response = client.get(&url).send()?;
-        
-        if !response.status().is_success() {
-            bail!(
-                "Failed to fetch package @{}/{} from {}: HTTP {}",
-                namespace,
-                name,
-                url,
-                response.status()
-            );
-        }
-        
-        let content = response.text()?;
+        let content = fetch_blocking(&url, package_fetch_timeout(), namespace, name)?;
         
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -152,6 +262,7 @@ pub async fn load_or_fetch_package(
         );
     }
 
+    ensure_online(namespace, name)?;
     validate_repo_url(repo_url)?;
     
     let url = format!(
@@ -159,13 +270,16 @@ pub async fn load_or_fetch_package(
         repo_url, namespace, name, name
     );
 
+    check_host_allowed(&url)?;
     eprintln!("Fetching package from: {}", url);
 
+    let timeout = package_fetch_timeout();
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(timeout)
         .build()?;
     
-    let response = client.get(&url).send().await?;
+    let response = client.get(&url).send().await
+        .map_err(|e| fetch_error(e, timeout, namespace, name, &url))?;
 
     if !response.status().is_success() {
         bail!(
@@ -177,7 +291,8 @@ pub async fn load_or_fetch_package(
         );
     }
 
-    let content = response.text().await?;
+    let content = response.text().await
+        .map_err(|e| fetch_error(e, timeout, namespace, name, &url))?;
 
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -189,4 +304,57 @@ pub async fn load_or_fetch_package(
 
 pub fn get_default_repo_url(_namespace: &str) -> String {
     "scholzdev/githook-packages".to_string()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://raw.githubusercontent.com/scholzdev/githook-packages/refs/heads/main/std/git/git.ghook";
+
+    fn hosts(hosts: &[&str]) -> Vec<String> {
+        hosts.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn allowlisted_host_passes() {
+        assert!(check_host(URL, true, &hosts(&["raw.githubusercontent.com"])).is_ok());
+        assert!(check_host(URL, true, &hosts(&["example.com", "RAW.githubusercontent.com"])).is_ok());
+    }
+
+    #[test]
+    fn disallowed_host_errors() {
+        let err = check_host(URL, true, &hosts(&["example.com"])).unwrap_err();
+        assert!(err.to_string().contains("Host 'raw.githubusercontent.com' is not in the allowed hosts"));
+
+        let err = check_host(URL, true, &[]).unwrap_err();
+        assert!(err.to_string().contains("allowed hosts (none)"));
+    }
+
+    #[test]
+    fn network_is_refused_unless_enabled() {
+        let err = check_host(URL, false, &hosts(&["raw.githubusercontent.com"])).unwrap_err();
+        assert!(err.to_string().contains("Network access is disabled"));
+        assert!(!is_network_allowed());
+    }
+
+    #[test]
+    fn slow_server_times_out_with_offline_hint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/std/git/git.ghook", listener.local_addr().unwrap());
+        // Accept the connection but never answer it
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            std::thread::sleep(Duration::from_secs(3));
+            drop(stream);
+        });
+
+        let started = std::time::Instant::now();
+        let err = fetch_blocking(&url, Duration::from_secs(1), "std", "git").unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        let message = err.to_string();
+        assert!(message.contains("Timed out after 1s fetching package @std/git"), "{message}");
+        assert!(message.contains("--offline"), "{message}");
+        server.join().unwrap();
+    }
+}
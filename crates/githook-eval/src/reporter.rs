@@ -0,0 +1,211 @@
+use crate::context::ExecutionContext;
+use crate::executor::ExecutionStatus;
+use colored::*;
+use githook_syntax::Span;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub trait Reporter {
+    fn group(&mut self, name: &str, severity: &str);
+    /// `span` is where the group is defined in the hook file
+    fn group_result(&mut self, name: &str, passed: bool, span: &Span);
+    fn check(&mut self, description: &str);
+    fn warning(&mut self, message: &str);
+    fn block(&mut self, message: &str);
+    fn detail(&mut self, line: &str);
+    fn note(&mut self, message: &str);
+    fn summary(&mut self, context: &ExecutionContext, status: &ExecutionStatus);
+}
+
+#[derive(Debug, Default)]
+pub struct HumanReporter {
+    quiet: bool,
+}
+
+impl HumanReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only prints failures, warnings and blocks.
+    pub fn quiet() -> Self {
+        Self { quiet: true }
+    }
+}
+
+impl Reporter for HumanReporter {
+    fn group(&mut self, name: &str, severity: &str) {
+        if self.quiet {
+            return;
+        }
+        println!("\n{} [{}]", format!("- {}", name).cyan().bold(), severity.yellow());
+    }
+
+    fn group_result(&mut self, name: &str, passed: bool, span: &Span) {
+        if passed {
+            if self.quiet {
+                return;
+            }
+            println!("{} Group '{}' passed", "o".green().bold(), name.green());
+        } else {
+            println!("{} Group '{}' failed {}", "x".red().bold(), name.red(), format!("(line {})", span.line).dimmed());
+        }
+    }
+
+    fn check(&mut self, _description: &str) {}
+
+    fn warning(&mut self, message: &str) {
+        println!("  {} {}", "-".yellow(), message.yellow());
+    }
+
+    fn block(&mut self, message: &str) {
+        println!("  {} {}", "x".red(), message.red());
+    }
+
+    fn detail(&mut self, line: &str) {
+        println!("    {}", line.dimmed());
+    }
+
+    fn note(&mut self, message: &str) {
+        println!("  {} {}", "-".yellow(), message);
+    }
+
+    fn summary(&mut self, ctx: &ExecutionContext, _status: &ExecutionStatus) {
+        println!("\n{}", "═".repeat(50));
+
+        let checks = ctx.checks_run();
+
+        if checks == 0 {
+            println!("o {} checks completed", checks);
+        } else {
+            println!("o {} check{} completed", checks, if checks == 1 { "" } else { "s" });
+        }
+
+        if !self.quiet && !ctx.checks_passed().is_empty() {
+            println!("\no Passed checks:");
+            for check in ctx.checks_passed() {
+                match check.span {
+                    Some(span) => println!("  - {} {}", check.description, format!("(line {})", span.line).dimmed()),
+                    None => println!("  - {}", check.description),
+                }
+            }
+        }
+
+        if ctx.has_warnings() {
+            println!("\n{} Warnings:", "!".yellow());
+            for (warning, locations) in ctx.warnings() {
+                println!("  - {}", warning.yellow());
+                for loc in locations {
+                    println!("    in {}", loc.dimmed());
+                }
+            }
+        }
+
+        println!("{}", "═".repeat(50));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct JsonReporter {
+    groups: Vec<serde_json::Value>,
+    blocks: Vec<String>,
+}
+
+impl JsonReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn group(&mut self, _name: &str, _severity: &str) {}
+
+    fn group_result(&mut self, name: &str, passed: bool, span: &Span) {
+        self.groups.push(serde_json::json!({
+            "name": name,
+            "passed": passed,
+            "line": span.line,
+            "column": span.col,
+        }));
+    }
+
+    fn check(&mut self, _description: &str) {}
+
+    fn warning(&mut self, _message: &str) {}
+
+    fn block(&mut self, message: &str) {
+        self.blocks.push(message.to_string());
+    }
+
+    fn detail(&mut self, _line: &str) {}
+
+    fn note(&mut self, _message: &str) {}
+
+    fn summary(&mut self, ctx: &ExecutionContext, status: &ExecutionStatus) {
+        let status = match status {
+            ExecutionStatus::Ok => "ok",
+            ExecutionStatus::Warn => "warn",
+            ExecutionStatus::Block | ExecutionStatus::BlockOnWarnings => "block",
+        };
+
+        let warnings: Vec<serde_json::Value> = ctx.warnings()
+            .iter()
+            .map(|(message, locations)| serde_json::json!({
+                "message": message,
+                "locations": locations,
+            }))
+            .collect();
+
+        let passed: Vec<serde_json::Value> = ctx.checks_passed()
+            .iter()
+            .map(|check| serde_json::json!({
+                "description": check.description,
+                "line": check.span.map(|span| span.line),
+                "column": check.span.map(|span| span.col),
+            }))
+            .collect();
+
+        let report = serde_json::json!({
+            "status": status,
+            "checks_run": ctx.checks_run(),
+            "passed": passed,
+            "warnings": warnings,
+            "blocks": self.blocks,
+            "groups": self.groups,
+        });
+
+        println!("{}", report);
+    }
+}
+
+/// Prints nothing and only keeps block messages, shared with the caller of [`crate::Executor::run_source`]
+#[derive(Debug, Default)]
+pub struct RecordingReporter {
+    blocks: Rc<RefCell<Vec<String>>>,
+}
+
+impl RecordingReporter {
+    pub fn new(blocks: Rc<RefCell<Vec<String>>>) -> Self {
+        Self { blocks }
+    }
+}
+
+impl Reporter for RecordingReporter {
+    fn group(&mut self, _name: &str, _severity: &str) {}
+
+    fn group_result(&mut self, _name: &str, _passed: bool, _span: &Span) {}
+
+    fn check(&mut self, _description: &str) {}
+
+    fn warning(&mut self, _message: &str) {}
+
+    fn block(&mut self, message: &str) {
+        self.blocks.borrow_mut().push(message.to_string());
+    }
+
+    fn detail(&mut self, _line: &str) {}
+
+    fn note(&mut self, _message: &str) {}
+
+    fn summary(&mut self, _ctx: &ExecutionContext, _status: &ExecutionStatus) {}
+}
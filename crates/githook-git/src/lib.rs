@@ -23,6 +23,91 @@ fn get_commit_msg_cache() -> &'static Mutex<LruCache<String, String>> {
     })
 }
 
+static DIFF_RANGE: Mutex<Option<DiffRange>> = Mutex::new(None);
+static IGNORE_PATTERNS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// A `<base>..<head>` commit range that replaces the index as the source of
+/// staged files, diffs and file contents, used to replay hooks against history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRange {
+    pub base: String,
+    pub head: String,
+}
+
+impl DiffRange {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let Some((base, head)) = spec.split_once("..") else {
+            bail!("Invalid range '{}', expected <rev>..<rev>", spec);
+        };
+        // `a...b` diffs from the merge base, which replaying a range does not support
+        if head.starts_with('.') {
+            bail!("Invalid range '{}', three-dot ranges are not supported, use <rev>..<rev>", spec);
+        }
+
+        if base.is_empty() || head.is_empty() || base.starts_with('-') || head.starts_with('-') {
+            bail!("Invalid range '{}', expected <rev>..<rev>", spec);
+        }
+
+        Ok(Self { base: base.to_string(), head: head.to_string() })
+    }
+}
+
+pub fn set_diff_range(range: Option<DiffRange>) {
+    *DIFF_RANGE.lock().expect("Diff range lock should not be poisoned") = range;
+}
+
+pub fn diff_range() -> Option<DiffRange> {
+    DIFF_RANGE.lock().expect("Diff range lock should not be poisoned").clone()
+}
+
+/// Globs for generated or vendored files that every file list leaves out.
+pub fn set_ignore_patterns(patterns: Vec<String>) {
+    *IGNORE_PATTERNS.lock().expect("Ignore patterns lock should not be poisoned") = patterns;
+}
+
+pub fn ignore_patterns() -> Vec<String> {
+    IGNORE_PATTERNS.lock().expect("Ignore patterns lock should not be poisoned").clone()
+}
+
+/// `git diff` arguments comparing the index with HEAD, or the configured range.
+pub fn diff_args(options: &[&str]) -> Vec<String> {
+    diff_args_for(diff_range(), options)
+}
+
+fn diff_args_for(range: Option<DiffRange>, options: &[&str]) -> Vec<String> {
+    let mut args = vec!["diff".to_string()];
+    args.extend(options.iter().map(|o| o.to_string()));
+    match range {
+        Some(range) => {
+            args.push(range.base);
+            args.push(range.head);
+        }
+        None => args.push("--cached".to_string()),
+    }
+    args
+}
+
+fn git_capture_diff(options: &[&str], paths: &[&str]) -> Result<String> {
+    let mut args = diff_args(options);
+    if !paths.is_empty() {
+        args.push("--".to_string());
+        args.extend(paths.iter().map(|p| p.to_string()));
+    }
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    git_capture(&args)
+}
+
+/// Cache key for diff-derived data, scoped to HEAD or the configured range
+fn diff_cache_key(kind: &str) -> Result<String> {
+    match diff_range() {
+        Some(range) => Ok(format!("{}:{}..{}", kind, range.base, range.head)),
+        None => {
+            let head = git_capture(&["rev-parse", "HEAD"])?;
+            Ok(format!("{}:{}", kind, head.trim()))
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct DiffStats {
     pub files_changed: usize,
@@ -37,12 +122,21 @@ pub struct SecretFinding {
     pub line_content: String,
 }
 
+/// Size in bytes of the staged blob, read from the object header without loading the content
 pub fn get_staged_file_size_from_index(path: &str) -> Result<usize> {
-    let content = get_staged_file_content_from_index(path)?;
-    Ok(content.len())
+    let head = diff_range().map(|range| range.head).unwrap_or_default();
+    let output = git_capture(&["cat-file", "-s", &format!("{}:{}", head, path)])?;
+    output
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid object size for {}: '{}'", path, output))
 }
 
 pub fn git_capture(args: &[&str]) -> Result<String> {
+    Ok(git_capture_raw(args)?.trim().to_string())
+}
+
+/// Like [`git_capture`], but keeps leading whitespace that is part of the format
+fn git_capture_raw(args: &[&str]) -> Result<String> {
     let mut cmd = Command::new("git");
     cmd.args(args);
     let output = cmd.output()?;
@@ -54,7 +148,7 @@ pub fn git_capture(args: &[&str]) -> Result<String> {
         );
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 pub fn git_capture_streaming(args: &[&str]) -> Result<String> {
@@ -128,15 +222,133 @@ fn get_glob_regex(pattern: &str) -> Result<Regex> {
 }
 
 pub fn get_staged_files(pattern: &str) -> Result<Vec<String>> {
+    get_staged_files_with_filter("ACM", pattern)
+}
 
-    let output = git_capture(&["diff", "--cached", "--name-only", "--diff-filter=ACM"])?;
-    
-    let files: Vec<String> = output
+/// Staged files newly added in the index.
+pub fn get_added_files(pattern: &str) -> Result<Vec<String>> {
+    get_staged_files_with_filter("A", pattern)
+}
+
+/// Files whose deletion is staged.
+pub fn get_deleted_files(pattern: &str) -> Result<Vec<String>> {
+    get_staged_files_with_filter("D", pattern)
+}
+
+/// Files changed between `git_ref` and the working tree, excluding deletions.
+pub fn get_files_changed_since(git_ref: &str, pattern: &str) -> Result<Vec<String>> {
+    let output = git_capture(&changed_since_args(git_ref)?)?;
+    filter_files(parse_name_only(&output), pattern)
+}
+
+fn changed_since_args(git_ref: &str) -> Result<Vec<&str>> {
+    // A ref starting with `-` would be read as an option
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        bail!("Invalid git ref '{}'", git_ref);
+    }
+
+    Ok(vec!["diff", "--name-only", "--diff-filter=ACMR", git_ref])
+}
+
+/// Files added, copied, modified or renamed by the `HEAD` commit.
+pub fn get_commit_files(pattern: &str) -> Result<Vec<String>> {
+    let output = git_capture(&["show", "--name-only", "--diff-filter=ACMR", "--pretty=format:", "HEAD"])?;
+    filter_files(parse_name_only(&output), pattern)
+}
+
+/// Files with unstaged modifications plus untracked files that are not ignored.
+pub fn get_dirty_files() -> Result<Vec<String>> {
+    Ok(parse_porcelain_dirty(&git_capture_raw(&["status", "--porcelain", "-z"])?))
+}
+
+/// Dirty paths from `git status --porcelain -z`. Changes that are only staged
+/// don't count, since they are what is being committed.
+pub fn parse_porcelain_dirty(output: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+    while let Some(entry) = entries.next() {
+        let (Some(status), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+            continue;
+        };
+        // Renames and copies are followed by their original path
+        if status.starts_with(['R', 'C']) {
+            entries.next();
+        }
+        if status == "??" || !status.ends_with(' ') {
+            files.push(path.to_string());
+        }
+    }
+    files
+}
+
+pub fn is_worktree_clean() -> Result<bool> {
+    Ok(get_dirty_files()?.is_empty())
+}
+
+/// Whether the commit being made has no changes, e.g. `git commit --allow-empty`.
+pub fn is_empty_commit() -> Result<bool> {
+    Ok(git_capture_diff(&["--name-only"], &[])?.is_empty())
+}
+
+/// Files with unresolved merge conflicts.
+pub fn get_conflict_files(pattern: &str) -> Result<Vec<String>> {
+    let output = git_capture(&["diff", "--name-only", "--diff-filter=U"])?;
+    filter_files(parse_name_only(&output), pattern)
+}
+
+/// Whether `.gitattributes` stores `path` in Git LFS, so the index only holds a pointer.
+pub fn is_lfs_file(path: &str) -> Result<bool> {
+    let output = git_capture(&["check-attr", "filter", "--", path])?;
+    Ok(is_lfs_filter(&output))
+}
+
+/// Whether `git check-attr filter` output sets the `lfs` filter.
+fn is_lfs_filter(check_attr_output: &str) -> bool {
+    check_attr_output.trim_end().ends_with(": filter: lfs")
+}
+
+/// Whether a merge has been started but not yet committed.
+pub fn is_merge_in_progress() -> Result<bool> {
+    Ok(git_capture(&["rev-parse", "-q", "--verify", "MERGE_HEAD"]).is_ok())
+}
+
+fn remove_ignored(files: Vec<String>) -> Result<Vec<String>> {
+    let ignored = ignore_patterns();
+    if ignored.is_empty() {
+        return Ok(files);
+    }
+
+    let regexes = ignored
+        .iter()
+        .map(|p| get_glob_regex(p.trim()))
+        .collect::<Result<Vec<Regex>>>()?;
+
+    Ok(files
+        .into_iter()
+        .filter(|f| !regexes.iter().any(|r| r.is_match(f)))
+        .collect())
+}
+
+/// One path per non-empty line, as printed by `--name-only`.
+pub fn parse_name_only(output: &str) -> Vec<String> {
+    output
         .lines()
         .filter(|f| !f.is_empty())
         .map(|s| s.to_string())
-        .collect();
-    
+        .collect()
+}
+
+fn get_staged_files_with_filter(diff_filter: &str, pattern: &str) -> Result<Vec<String>> {
+    let filter_arg = format!("--diff-filter={}", diff_filter);
+    let output = git_capture_diff(&["--name-only", &filter_arg], &[])?;
+    filter_files(parse_name_only(&output), pattern)
+}
+
+/// Keep the files matching `pattern`, a glob or several globs separated by `|`,
+/// minus those matching an ignore pattern.
+pub fn filter_files(files: Vec<String>, pattern: &str) -> Result<Vec<String>> {
+    let files = remove_ignored(files)?;
+
     if pattern == "*" {
         return Ok(files);
     }
@@ -161,13 +373,87 @@ pub fn get_staged_files(pattern: &str) -> Result<Vec<String>> {
         .collect())
 }
 
+pub fn get_binary_staged_files() -> Result<Vec<String>> {
+    let output = git_capture_diff(&["--numstat"], &[])?;
+    Ok(parse_numstat_binary(&output))
+}
+
+/// Binary files show up in `--numstat` output as `-\t-\t<path>`.
+pub fn parse_numstat_binary(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("-\t-\t"))
+        .map(|path| path.to_string())
+        .collect()
+}
+
+pub fn is_file_binary(file: &str) -> Result<bool> {
+    Ok(get_binary_staged_files()?.iter().any(|f| f == file))
+}
+
+/// Fingerprint of each file's working tree content, `None` when it cannot be read.
+pub fn worktree_snapshot(files: &[String]) -> Vec<(String, Option<u64>)> {
+    files
+        .iter()
+        .map(|file| (file.clone(), content_fingerprint(file)))
+        .collect()
+}
+
+/// Files from `snapshot` whose working tree content is different now.
+pub fn changed_since_snapshot(snapshot: &[(String, Option<u64>)]) -> Vec<String> {
+    snapshot
+        .iter()
+        .filter(|(file, fingerprint)| content_fingerprint(file) != *fingerprint)
+        .map(|(file, _)| file.clone())
+        .collect()
+}
+
+fn content_fingerprint(file: &str) -> Option<u64> {
+    use std::hash::{Hash, Hasher};
+
+    let content = std::fs::read(file).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Stage `files` again after a fixer rewrote them. Diffs cached for HEAD
+/// are dropped, since the index no longer matches them.
+pub fn restage_files(files: &[String]) -> Result<()> {
+    let args = restage_args(files);
+    if args.is_empty() {
+        return Ok(());
+    }
+
+    git_capture(&args)?;
+    get_diff_cache().lock().expect("Diff cache lock should not be poisoned").clear();
+    Ok(())
+}
+
+/// `git add` arguments for [`restage_files`], empty when there is nothing to stage
+pub fn restage_args(files: &[String]) -> Vec<&str> {
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    let mut args = vec!["add", "--"];
+    args.extend(files.iter().map(|f| f.as_str()));
+    args
+}
+
 pub fn is_file_staged(pattern: &str) -> Result<bool> {
     let files = get_staged_files(pattern)?;
     Ok(!files.is_empty())
 }
 
+pub fn get_file_diff(file: &str) -> Result<String> {
+    git_capture_diff(&[], &[file])
+}
+
 pub fn get_staged_file_content_from_index(file: &str) -> Result<String> {
-    git_capture(&["show", &format!(":{}", file)])
+    // `:<path>` is the index entry, `<rev>:<path>` the file at the range head
+    let head = diff_range().map(|range| range.head).unwrap_or_default();
+    git_capture(&["show", &format!("{}:{}", head, file)])
 }
 
 pub fn get_staged_file_contents_batch(files: &[String]) -> Result<HashMap<String, String>> {
@@ -241,8 +527,7 @@ pub fn get_all_files(pattern: &str) -> Result<Vec<String>> {
 }
 
 pub fn get_diff_added_lines() -> Result<String> {
-    let head = git_capture(&["rev-parse", "HEAD"])?;
-    let cache_key = format!("diff_added:{}", head.trim());
+    let cache_key = diff_cache_key("diff_added")?;
     
     {
         let mut cache = get_diff_cache().lock()
@@ -252,14 +537,7 @@ pub fn get_diff_added_lines() -> Result<String> {
         }
     }
     
-    let output = git_capture(&["diff", "--cached"])?;
-
-    let added_lines: Vec<&str> = output
-        .lines()
-        .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
-        .collect();
-
-    let result = added_lines.join("\n");
+    let result = added_lines(&get_staged_diff()?);
     
     {
         let mut cache = get_diff_cache().lock()
@@ -270,9 +548,47 @@ pub fn get_diff_added_lines() -> Result<String> {
     Ok(result)
 }
 
+pub fn get_staged_diff() -> Result<String> {
+    let cache_key = diff_cache_key("diff_full")?;
+
+    {
+        let mut cache = get_diff_cache().lock()
+            .expect("Diff cache lock should not be poisoned");
+        if let Some(cached) = cache.get(&cache_key) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let output = git_capture_diff(&[], &[])?;
+
+    {
+        let mut cache = get_diff_cache().lock()
+            .expect("Diff cache lock should not be poisoned");
+        cache.put(cache_key, output.clone());
+    }
+
+    Ok(output)
+}
+
+pub fn diff_contains(text: &str) -> Result<bool> {
+    Ok(get_staged_diff()?.contains(text))
+}
+
+/// Whether the added lines contain `text`. Lines keep their leading `+`, as in `added_lines`.
+pub fn diff_added_contains(text: &str) -> Result<bool> {
+    Ok(get_diff_added_lines()?.contains(text))
+}
+
+/// Lines added by a unified diff, each with its leading `+`, joined by newlines
+pub fn added_lines(diff: &str) -> String {
+    diff.lines()
+        .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub fn get_diff_stats() -> Result<DiffStats> {
-    let head = git_capture(&["rev-parse", "HEAD"])?;
-    let cache_key = format!("diff_stats:{}", head.trim());
+    let cache_key = diff_cache_key("diff_stats")?;
     
     {
         let mut cache = get_diff_cache().lock()
@@ -289,7 +605,7 @@ pub fn get_diff_stats() -> Result<DiffStats> {
         }
     }
     
-    let output = git_capture(&["diff", "--cached", "--shortstat"])?;
+    let output = git_capture_diff(&["--shortstat"], &[])?;
 
     let mut stats = DiffStats::default();
 
@@ -318,6 +634,40 @@ pub fn get_diff_stats() -> Result<DiffStats> {
     Ok(stats)
 }
 
+/// Diff statistics restricted to the files matching `pattern`
+pub fn get_diff_stats_for(pattern: &str) -> Result<DiffStats> {
+    let output = git_capture_diff(&["--numstat", "--no-renames"], &[])?;
+    sum_numstat(&output, pattern)
+}
+
+/// Sum `--numstat` output over the files matching `pattern`; binary files add no lines.
+pub fn sum_numstat(output: &str, pattern: &str) -> Result<DiffStats> {
+    let mut per_file = HashMap::new();
+    for line in output.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        per_file.insert(
+            path.to_string(),
+            (added.parse::<usize>().unwrap_or(0), deleted.parse::<usize>().unwrap_or(0)),
+        );
+    }
+
+    let files = filter_files(per_file.keys().cloned().collect(), pattern)?;
+    let mut stats = DiffStats {
+        files_changed: files.len(),
+        ..DiffStats::default()
+    };
+    for file in &files {
+        let (added, deleted) = per_file[file];
+        stats.additions += added;
+        stats.deletions += deleted;
+    }
+
+    Ok(stats)
+}
+
 pub fn get_modified_lines() -> Result<usize> {
     let stats = get_diff_stats()?;
     Ok(stats.additions + stats.deletions)
@@ -335,6 +685,52 @@ pub fn get_remote_url() -> Result<String> {
     git_capture(&["config", "--get", "remote.origin.url"])
 }
 
+/// Host and repository path of a remote URL. All fields are empty if the URL is not recognized.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RemoteInfo {
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+/// Split an SSH (`git@host:owner/repo.git`) or URL-style (`https://host/owner/repo`) remote.
+/// For nested namespaces the owner keeps every segment but the last (`group/subgroup`).
+pub fn parse_remote_url(url: &str) -> RemoteInfo {
+    let url = url.trim();
+
+    let (authority, path) = if let Some((_, rest)) = url.split_once("://") {
+        match rest.split_once('/') {
+            Some(parts) => parts,
+            None => return RemoteInfo::default(),
+        }
+    } else {
+        match url.split_once(':') {
+            Some((authority, path)) if !authority.contains('/') => (authority, path),
+            _ => return RemoteInfo::default(),
+        }
+    };
+
+    // Drop `user@` and `:port`
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = host.split(':').next().unwrap_or(host);
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    match path.rsplit_once('/') {
+        Some((owner, repo)) if !host.is_empty() && !owner.is_empty() && !repo.is_empty() => RemoteInfo {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        },
+        _ => RemoteInfo::default(),
+    }
+}
+
+pub fn get_remote_info() -> Result<RemoteInfo> {
+    Ok(parse_remote_url(&get_remote_url()?))
+}
+
 pub fn get_staged_blob_oid(path: &str) -> Result<String> {
     let output = git_capture(&["ls-files", "-s", path])?;
     let parts: Vec<&str> = output.split_whitespace().collect();
@@ -372,6 +768,43 @@ pub fn get_commits_ahead(remote_branch: &str) -> Result<usize> {
     Ok(count)
 }
 
+/// One-line summaries (`<short sha> <subject>`) of commits not yet on `remote_branch`, newest first
+pub fn get_unpushed_commits(remote_branch: &str) -> Result<Vec<String>> {
+    let output = git_capture(&["log", "--oneline", "--no-decorate", &format!("{}..HEAD", remote_branch)])?;
+
+    Ok(output
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// All tag names, sorted by git.
+pub fn get_tags() -> Result<Vec<String>> {
+    let output = git_capture(&["tag", "--list"])?;
+    Ok(parse_name_only(&output))
+}
+
+pub fn tag_exists(name: &str) -> Result<bool> {
+    match tag_ref(name) {
+        Some(tag) => Ok(git_capture(&["rev-parse", "-q", "--verify", &tag]).is_ok()),
+        None => Ok(false),
+    }
+}
+
+/// Full ref of a tag, `None` for names git would read as an option.
+fn tag_ref(name: &str) -> Option<String> {
+    if name.is_empty() || name.starts_with('-') {
+        return None;
+    }
+    Some(format!("refs/tags/{}", name))
+}
+
+/// The most recent tag reachable from `HEAD`.
+pub fn get_latest_tag() -> Result<String> {
+    git_capture(&["describe", "--tags", "--abbrev=0"])
+}
+
 pub fn get_commit_message_from_hook_args(hook_args: &[String]) -> Result<String> {
     if hook_args.is_empty() {
         bail!("No commit message file provided in hook args");
@@ -423,6 +856,21 @@ pub fn get_commit_message_from_hook_args(hook_args: &[String]) -> Result<String>
     Ok(content)
 }
 
+/// Length in characters of the first line of a commit message.
+pub fn subject_length(msg: &str) -> usize {
+    msg.lines().next().unwrap_or("").trim_end().chars().count()
+}
+
+/// Length in characters of the longest body line, ignoring `#` comment lines.
+pub fn max_body_line_length(msg: &str) -> usize {
+    msg.lines()
+        .skip(1)
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.trim_end().chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
 pub fn is_author_set() -> Result<bool> {
     let output = git_capture(&["config", "user.name"])?;
     Ok(!output.trim().is_empty())
@@ -433,20 +881,32 @@ pub fn is_author_email_set() -> Result<bool> {
     Ok(!output.trim().is_empty())
 }
 
-pub fn get_max_file_size() -> Result<f64> {
+/// Staged file with the most bytes on disk, with its size, or `None` when nothing is staged
+pub fn get_largest_staged_file() -> Result<Option<(String, usize)>> {
     let files = get_staged_files("*")?;
-    let mut max_size = 0.0;
+    Ok(largest_file(files, |file| {
+        std::fs::metadata(file).ok().map(|metadata| metadata.len() as usize)
+    }))
+}
+
+/// File with the greatest size. Files without a size are skipped; ties keep the first file.
+fn largest_file(files: Vec<String>, size_of: impl Fn(&str) -> Option<usize>) -> Option<(String, usize)> {
+    let mut largest: Option<(String, usize)> = None;
 
     for file in files {
-        if let Ok(metadata) = std::fs::metadata(&file) {
-            let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-            if size_mb > max_size {
-                max_size = size_mb;
-            }
+        if let Some(size) = size_of(&file)
+            && largest.as_ref().is_none_or(|(_, max)| size > *max)
+        {
+            largest = Some((file, size));
         }
     }
 
-    Ok(max_size)
+    largest
+}
+
+pub fn get_max_file_size() -> Result<f64> {
+    let largest = get_largest_staged_file()?;
+    Ok(largest.map_or(0.0, |(_, size)| size as f64 / (1024.0 * 1024.0)))
 }
 
 fn get_secret_patterns() -> &'static [Regex; 6] {
@@ -557,4 +1017,231 @@ pub fn get_commit_message() -> Result<String> {
     let output = git_capture(&["log", "-1", "--pretty=%B"])?;
     
     Ok(output.trim().to_string())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_two_dot_ranges() {
+        let range = DiffRange::parse("main..feature").unwrap();
+        assert_eq!(range.base, "main");
+        assert_eq!(range.head, "feature");
+    }
+
+    #[test]
+    fn rejects_three_dot_and_malformed_ranges() {
+        assert!(DiffRange::parse("main...feature").is_err());
+        assert!(DiffRange::parse("main").is_err());
+        assert!(DiffRange::parse("..feature").is_err());
+        assert!(DiffRange::parse("main..--output=x").is_err());
+    }
+
+    #[test]
+    fn diff_args_use_the_range_instead_of_the_index() {
+        let range = DiffRange::parse("v1..HEAD").unwrap();
+        assert_eq!(diff_args_for(Some(range), &["--name-only"]), vec!["diff", "--name-only", "v1", "HEAD"]);
+        assert_eq!(diff_args_for(None, &["--name-only"]), vec!["diff", "--name-only", "--cached"]);
+    }
+
+    #[test]
+    fn restage_args_add_the_given_paths() {
+        let files = vec!["src/main.rs".to_string(), "-odd name.rs".to_string()];
+        assert_eq!(restage_args(&files), vec!["add", "--", "src/main.rs", "-odd name.rs"]);
+    }
+
+    #[test]
+    fn restage_args_are_empty_without_files() {
+        assert!(restage_args(&[]).is_empty());
+    }
+
+    #[test]
+    fn changed_since_args_diff_against_the_ref() {
+        assert_eq!(
+            changed_since_args("origin/main").unwrap(),
+            vec!["diff", "--name-only", "--diff-filter=ACMR", "origin/main"]
+        );
+        assert!(changed_since_args("").is_err());
+        assert!(changed_since_args("--output=x").is_err());
+    }
+
+    #[test]
+    fn changed_since_output_is_split_and_filtered() {
+        let output = "src/lib.rs\n\nREADME.md\nsrc/git.rs\n";
+        assert_eq!(parse_name_only(output), vec!["src/lib.rs", "README.md", "src/git.rs"]);
+        assert_eq!(
+            filter_files(parse_name_only(output), "src/*.rs").unwrap(),
+            vec!["src/lib.rs", "src/git.rs"]
+        );
+    }
+
+    #[test]
+    fn commit_files_parse_git_show_output() {
+        // `--pretty=format:` leaves an empty header line before the file list
+        let output = "\nsrc/main.rs\ndocs/guide.md\nsrc/git/mod.rs\n";
+        assert_eq!(parse_name_only(output), vec!["src/main.rs", "docs/guide.md", "src/git/mod.rs"]);
+        assert_eq!(filter_files(parse_name_only(output), "docs/*.md|src/*.rs").unwrap(), vec!["src/main.rs", "docs/guide.md"]);
+        assert!(parse_name_only("\n").is_empty());
+    }
+
+    #[test]
+    fn conflict_files_parse_unmerged_diff_output() {
+        // `git diff --name-only --diff-filter=U` during a conflicted merge
+        let output = "f.txt\nsrc/lib.rs\n";
+        assert_eq!(parse_name_only(output), vec!["f.txt", "src/lib.rs"]);
+        assert_eq!(filter_files(parse_name_only(output), "src/*.rs").unwrap(), vec!["src/lib.rs"]);
+        assert!(parse_name_only("").is_empty());
+    }
+
+    #[test]
+    fn tag_list_is_parsed_one_per_line() {
+        let tags = parse_name_only("v1.0.0\nv1.1.0\nrelease/2024\n");
+        assert_eq!(tags, vec!["v1.0.0", "v1.1.0", "release/2024"]);
+        assert!(tags.iter().any(|t| t == "v1.1.0"));
+        assert!(!tags.iter().any(|t| t == "v1.1"));
+        assert!(parse_name_only("").is_empty());
+    }
+
+    #[test]
+    fn tag_refs_reject_option_like_names() {
+        assert_eq!(tag_ref("v1.0.0").as_deref(), Some("refs/tags/v1.0.0"));
+        assert_eq!(tag_ref(""), None);
+        assert_eq!(tag_ref("--all"), None);
+        assert!(!tag_exists("-v1").unwrap());
+    }
+
+    #[test]
+    fn psd_files_are_classified_as_lfs() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git").arg("-C").arg(dir.path()).args(args).output().unwrap();
+            String::from_utf8(output.stdout).unwrap()
+        };
+        git(&["init", "-q"]);
+        std::fs::write(dir.path().join(".gitattributes"), "*.psd filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+
+        assert!(is_lfs_filter(&git(&["check-attr", "filter", "--", "art/cover.psd"])));
+        assert!(!is_lfs_filter(&git(&["check-attr", "filter", "--", "src/main.rs"])));
+        assert!(!is_lfs_filter("cover.png: filter: unspecified\n"));
+    }
+
+    fn remote(host: &str, owner: &str, repo: &str) -> RemoteInfo {
+        RemoteInfo {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_ssh_remotes() {
+        assert_eq!(parse_remote_url("git@github.com:scholzdev/githook.git"), remote("github.com", "scholzdev", "githook"));
+        assert_eq!(parse_remote_url("ssh://git@gitlab.com:2222/group/sub/app.git"), remote("gitlab.com", "group/sub", "app"));
+    }
+
+    #[test]
+    fn parses_https_remotes() {
+        assert_eq!(parse_remote_url("https://github.com/scholzdev/githook"), remote("github.com", "scholzdev", "githook"));
+        assert_eq!(parse_remote_url("https://user@github.com/scholzdev/githook.git/\n"), remote("github.com", "scholzdev", "githook"));
+    }
+
+    #[test]
+    fn unrecognized_remotes_are_empty() {
+        assert_eq!(parse_remote_url(""), RemoteInfo::default());
+        assert_eq!(parse_remote_url("/srv/git/githook.git"), RemoteInfo::default());
+        assert_eq!(parse_remote_url("https://github.com"), RemoteInfo::default());
+    }
+
+    const NUMSTAT: &str = "10\t2\tsrc/main.rs\n3\t0\tsrc/lib.rs\n-\t-\tlogo.png\n7\t7\tREADME.md\n";
+
+    #[test]
+    fn numstat_sums_only_matching_files() {
+        let stats = sum_numstat(NUMSTAT, "src/*.rs").unwrap();
+        assert_eq!((stats.files_changed, stats.additions, stats.deletions), (2, 13, 2));
+
+        let stats = sum_numstat(NUMSTAT, "*.md|*.png").unwrap();
+        assert_eq!((stats.files_changed, stats.additions, stats.deletions), (2, 7, 7));
+    }
+
+    #[test]
+    fn numstat_sums_every_file_for_star() {
+        let stats = sum_numstat(NUMSTAT, "*").unwrap();
+        assert_eq!((stats.files_changed, stats.additions, stats.deletions), (4, 20, 9));
+    }
+
+    #[test]
+    fn clean_porcelain_has_no_dirty_files() {
+        assert!(parse_porcelain_dirty("").is_empty());
+        assert!(parse_porcelain_dirty("M  src/lib.rs\0A  new.rs\0R  renamed.rs\0old.rs\0").is_empty());
+    }
+
+    #[test]
+    fn dirty_porcelain_lists_unstaged_and_untracked_files() {
+        let output = " M src/main.rs\0MM src/lib.rs\0?? notes.txt\0RM moved.rs\0orig.rs\0A  staged.rs\0 D gone.rs\0";
+        assert_eq!(
+            parse_porcelain_dirty(output),
+            vec!["src/main.rs", "src/lib.rs", "notes.txt", "moved.rs", "gone.rs"]
+        );
+    }
+
+    #[test]
+    fn largest_file_picks_the_biggest_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<String> = [("small.txt", 10), ("big.bin", 4096), ("medium.rs", 512)]
+            .iter()
+            .map(|(name, size)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, vec![b'x'; *size]).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .chain(std::iter::once(dir.path().join("deleted.txt").to_string_lossy().into_owned()))
+            .collect();
+
+        let size_on_disk = |file: &str| std::fs::metadata(file).ok().map(|m| m.len() as usize);
+        assert_eq!(largest_file(files.clone(), size_on_disk), Some((files[1].clone(), 4096)));
+        assert_eq!(largest_file(Vec::new(), size_on_disk), None);
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,2 +1,2 @@
+ fn main() {
+-    println!(\"old\");
++    dbg!(x);
+}";
+
+    #[test]
+    fn added_lines_contain_added_text() {
+        let added = added_lines(DIFF);
+        assert_eq!(added, "+    dbg!(x);");
+        assert!(added.contains("dbg!"));
+        assert!(added.contains("+    dbg"));
+    }
+
+    #[test]
+    fn added_lines_skip_removed_and_header_lines() {
+        let added = added_lines(DIFF);
+        assert!(!added.contains("old"));
+        assert!(!added.contains("src/main.rs"));
+        assert!(!added.contains("fn main"));
+    }
+
+    #[test]
+    fn subject_length_counts_characters_up_to_the_72_boundary() {
+        let at_limit = "a".repeat(72);
+        assert_eq!(subject_length(&format!("{at_limit}\n\nbody")), 72);
+        assert_eq!(subject_length(&format!("{at_limit}b  \n")), 73);
+        assert_eq!(subject_length(&"ü".repeat(72)), 72);
+        assert_eq!(subject_length(""), 0);
+    }
+
+    #[test]
+    fn max_body_line_length_finds_the_line_over_the_limit() {
+        let long = "x".repeat(73);
+        let msg = format!("{}\n\nshort line\n{long}\n# {long}{long}\n", "s".repeat(100));
+        assert_eq!(max_body_line_length(&msg), 73);
+        assert_eq!(max_body_line_length("subject only"), 0);
+    }
+}
@@ -18,6 +18,7 @@ use crate::folding::get_folding_ranges;
 use crate::codelens::get_code_lens;
 use crate::semantic_tokens::{get_legend, get_semantic_tokens};
 use crate::documentlinks::get_document_links;
+use crate::inlay_hints::get_inlay_hints;
 
 /// Main LSP backend for Githook language
 pub struct GithookLanguageServer {
@@ -96,6 +97,7 @@ impl LanguageServer for GithookLanguageServer {
                     resolve_provider: Some(false),
                     work_done_progress_options: WorkDoneProgressOptions::default(),
                 }),
+                inlay_hint_provider: Some(OneOf::Left(true)),
                 ..Default::default()
             },
         })
@@ -242,7 +244,7 @@ impl LanguageServer for GithookLanguageServer {
         let documents = self.documents.read().await;
         
         if let Some(doc) = documents.get(&uri) {
-            let ranges = get_folding_ranges(&doc.ast);
+            let ranges = get_folding_ranges(&doc.ast, &doc.text);
             return Ok(Some(ranges));
         }
         
@@ -306,4 +308,16 @@ impl LanguageServer for GithookLanguageServer {
         
         Ok(None)
     }
+    
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let uri = params.text_document.uri.to_string();
+        let documents = self.documents.read().await;
+        
+        if let Some(doc) = documents.get(&uri) {
+            let hints = get_inlay_hints(&doc.ast, &doc.text, params.range);
+            return Ok(Some(hints));
+        }
+        
+        Ok(None)
+    }
 }
@@ -2,9 +2,27 @@ use tower_lsp::lsp_types::*;
 use crate::document::DocumentState;
 use std::collections::HashMap;
 
-/// Get code lens (reference counts) for symbols
+/// Command id the editor extension binds to execute a hook file
+pub const RUN_HOOK_COMMAND: &str = "githook.runHook";
+
+/// Get code lens (run action and reference counts) for symbols
 pub fn get_code_lens(doc: &DocumentState, all_documents: &HashMap<String, DocumentState>, current_uri: &str) -> Vec<CodeLens> {
     let mut lenses = Vec::new();
+
+    if !doc.text.trim().is_empty() {
+        lenses.push(CodeLens {
+            range: Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 0 },
+            },
+            command: Some(Command {
+                title: "▶ Run this hook".to_string(),
+                command: RUN_HOOK_COMMAND.to_string(),
+                arguments: Some(vec![serde_json::json!(current_uri)]),
+            }),
+            data: None,
+        });
+    }
     
     for (name, span, _body) in &doc.macro_definitions {
         // Count local references in current document
@@ -52,3 +70,33 @@ pub fn get_code_lens(doc: &DocumentState, all_documents: &HashMap<String, Docume
     
     lenses
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///repo/.githook/pre-commit.ghook";
+
+    fn lenses_for(text: &str) -> Vec<CodeLens> {
+        let doc = DocumentState::new(text.to_string(), Some(URI));
+        get_code_lens(&doc, &HashMap::new(), URI)
+    }
+
+    #[test]
+    fn non_empty_document_has_one_run_lens() {
+        let lenses = lenses_for("run \"cargo test\"\n");
+        assert_eq!(lenses.len(), 1);
+
+        let lens = &lenses[0];
+        assert_eq!(lens.range.start, Position { line: 0, character: 0 });
+        let command = lens.command.as_ref().unwrap();
+        assert_eq!(command.command, RUN_HOOK_COMMAND);
+        assert_eq!(command.arguments, Some(vec![serde_json::json!(URI)]));
+    }
+
+    #[test]
+    fn empty_document_has_no_lens() {
+        assert!(lenses_for("").is_empty());
+        assert!(lenses_for("  \n\n").is_empty());
+    }
+}
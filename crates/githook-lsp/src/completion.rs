@@ -36,6 +36,7 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
         ("run", "Execute a shell command", CompletionItemKind::KEYWORD),
         ("block", "Block the commit with a message", CompletionItemKind::KEYWORD),
         ("allow", "Allow a specific command", CompletionItemKind::KEYWORD),
+        ("require", "Require a clean working tree (require clean)", CompletionItemKind::KEYWORD),
         ("when", "Conditional execution", CompletionItemKind::KEYWORD),
         ("match", "Pattern matching", CompletionItemKind::KEYWORD),
         ("foreach", "Iterate over files", CompletionItemKind::KEYWORD),
@@ -63,6 +64,11 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
     // File Collections (for foreach loops)
     let file_collections = vec![
         ("staged_files", "All staged files", "foreach file in staged_files matching \"*.rs\""),
+        ("added_files", "Newly added staged files", "foreach file in added_files where file_size > 1048576"),
+        ("changed_since", "Files changed since a git ref", "foreach file in changed_since \"main\" matching \"*.rs\""),
+        ("commit_files", "Files in the last commit (post-commit)", "foreach file in commit_files matching \"*.rs\""),
+        ("conflict_files", "Files with unresolved merge conflicts", "foreach file in conflict_files"),
+        ("deleted_files", "Files staged for deletion", "foreach file in deleted_files matching \"*.lock\""),
         ("all_files", "All files in repo", "foreach file in all_files matching \"*.md\""),
     ];
 
@@ -81,6 +87,7 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
         ("content", "File content", "content matches \"pattern\""),
         ("staged_content", "Staged file content", "staged_content contains \"TODO\""),
         ("diff", "Staged changes diff", "diff matches \"^-.*old\""),
+        ("added_lines", "Added lines of the staged diff", "added_lines contains \"dbg!\""),
         ("branch_name", "Git branch name", "branch_name matches \"^main$\""),
         ("commit_message", "Commit message text", "commit_message contains \"fix\""),
         ("author_email", "Git author email", "author_email matches \"@company.com\""),
@@ -105,9 +112,12 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
         ("file_size", "File size in bytes", "file_size > 1048576"),
         ("modified_lines", "Changed lines in diff", "modified_lines > 500"),
         ("files_changed", "Number of changed files", "files_changed == 5"),
+        ("staged_count", "Number of staged files", "staged_count > 50"),
         ("additions", "Added lines", "additions >= 10"),
         ("deletions", "Deleted lines", "deletions < 100"),
         ("commits_ahead", "Commits ahead of remote", "commits_ahead > 0"),
+        ("subject_length", "Commit subject length in characters", "subject_length > 72"),
+        ("max_line_length", "Longest commit body line", "max_line_length > 100"),
     ];
 
     for (label, detail, example) in numeric_properties {
@@ -126,6 +136,13 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
         ("author_email_set", "Git user.email is set", "block_if not author_email_set"),
         ("author_missing", "Git author not configured", "block_if author_missing"),
         ("contains_secrets", "Secrets/credentials detected", "block_if contains_secrets"),
+        ("is_binary", "Current staged file is binary", "when not is_binary { ... }"),
+        ("any_binary", "Any staged file is binary", "warn_if any_binary"),
+        ("merge_in_progress", "A merge is being committed", "when merge_in_progress"),
+        ("has_conflicts", "Unresolved merge conflicts exist", "block_if has_conflicts"),
+        ("tag_exists", "A tag with this name exists", "block_if tag_exists \"v1.0.0\""),
+        ("empty_commit", "The commit has no staged changes", "when not empty_commit"),
+        ("worktree_dirty", "Unstaged or untracked changes exist", "block_if worktree_dirty"),
         ("file_exists", "File exists check", "block_if not file_exists"),
     ];
 
@@ -142,6 +159,7 @@ pub fn get_completions(doc: &DocumentState, position: Position) -> Vec<Completio
     // Operators
     let operators = vec![
         ("matches", "Regex match operator", "content matches \"^TODO\""),
+        ("ignorecase", "Case-insensitive regex match", "branch_name matches \"^feature/\" ignorecase"),
         ("contains", "String contains operator", "content contains \"panic!\""),
         ("==", "Equality operator", "file_size == 0"),
         ("!=", "Not equals operator", "branch_name != \"main\""),
@@ -261,6 +279,8 @@ fn get_placeholder_completions(prefix: &str) -> Vec<CompletionItem> {
                 ("extension", "File extension"),
                 ("dirname", "Directory path"),
                 ("size", "File size in bytes"),
+                ("diff", "Staged diff of the file"),
+                ("is_lfs", "Whether the file is stored in Git LFS"),
             ];
             
             for (name, detail) in placeholders {
@@ -281,6 +301,10 @@ fn get_placeholder_completions(prefix: &str) -> Vec<CompletionItem> {
                 ("author_email", "Author email"),
                 ("repo_root", "Repository root path"),
                 ("remote_url", "Remote URL"),
+                ("commits_ahead", "Commits not yet pushed to origin"),
+                ("unpushed_commits", "One-line summaries of unpushed commits"),
+                ("tags", "All tag names"),
+                ("latest_tag", "Most recent tag reachable from HEAD"),
             ];
             
             for (name, detail) in placeholders {
@@ -296,9 +320,12 @@ fn get_placeholder_completions(prefix: &str) -> Vec<CompletionItem> {
         "commit" => {
             let placeholders = vec![
                 ("message", "Commit message"),
+                ("subject_length", "Subject length in characters"),
+                ("max_line_length", "Longest body line"),
                 ("files", "Number of changed files"),
                 ("additions", "Added lines"),
                 ("deletions", "Deleted lines"),
+                ("is_empty", "Whether nothing is staged"),
             ];
             
             for (name, detail) in placeholders {
@@ -315,6 +342,9 @@ fn get_placeholder_completions(prefix: &str) -> Vec<CompletionItem> {
             let placeholders = vec![
                 ("added", "Added lines (only + lines)"),
                 ("stats", "Diff statistics"),
+                ("is_empty", "Whether no files changed"),
+                ("largest_file", "Path of the biggest staged file"),
+                ("largest_file_size", "Size in bytes of the biggest staged file"),
             ];
             
             for (name, detail) in placeholders {
@@ -332,6 +362,9 @@ fn get_placeholder_completions(prefix: &str) -> Vec<CompletionItem> {
                 ("root", "Repository root path"),
                 ("name", "Repository name"),
                 ("has_remote", "Has remote (true/false)"),
+                ("remote_host", "Remote host (e.g. github.com)"),
+                ("remote_owner", "Remote owner or group"),
+                ("remote_repo", "Remote repository name"),
             ];
             
             for (name, detail) in placeholders {
@@ -1,60 +1,34 @@
 use tower_lsp::lsp_types::*;
 use tower_lsp::Client;
-use githook_syntax::{ParseError, Span};
+use githook_syntax::{Diagnostic as SyntaxDiagnostic, LineRange, ParseError};
 
-/// Convert our ParseError to LSP Diagnostic
-pub fn parse_error_to_diagnostic(error: &ParseError, _source: &str) -> Diagnostic {
-    let (message, span) = match error {
-        ParseError::UnexpectedToken { expected, found, span } => {
-            (format!("Expected {}, found {}", expected, found), Some(*span))
-        }
-        ParseError::UnexpectedEof { expected, context } => {
-            let msg = if let Some(ctx) = context {
-                format!("Unexpected end of file while parsing {}, expected {}", ctx, expected)
-            } else {
-                format!("Unexpected end of file, expected {}", expected)
-            };
-            (msg, None)
-        }
-        ParseError::MissingToken { expected, span } => {
-            (format!("Missing {}", expected), Some(*span))
-        }
-        ParseError::InvalidSyntax { message, span } => {
-            (message.clone(), Some(*span))
-        }
-        ParseError::LexError(lex_error) => {
-            (format!("{}", lex_error), Some(lex_error.span()))
-        }
-    };
-
-    let range = span.map(span_to_range).unwrap_or_else(|| Range {
-        start: Position { line: 0, character: 0 },
-        end: Position { line: 0, character: 0 },
-    });
+/// Convert our ParseError to LSP Diagnostic, at the same location the CLI reports
+pub fn parse_error_to_diagnostic(error: &ParseError, source: &str) -> Diagnostic {
+    let syntax_diagnostic = SyntaxDiagnostic::new_parse(source, error.clone());
 
     Diagnostic {
-        range,
+        range: line_range_to_range(syntax_diagnostic.range()),
         severity: Some(DiagnosticSeverity::ERROR),
-        code: None,
+        code: Some(NumberOrString::String(error.code().to_string())),
         code_description: None,
         source: Some("githook".to_string()),
-        message,
+        message: syntax_diagnostic.message(),
         related_information: None,
         tags: None,
         data: None,
     }
 }
 
-/// Convert our Span to LSP Range
-pub fn span_to_range(span: Span) -> Range {
+/// Convert our zero-based LineRange to LSP Range
+pub fn line_range_to_range(range: LineRange) -> Range {
     Range {
         start: Position {
-            line: span.line.saturating_sub(1) as u32, // Span is 1-indexed, LSP is 0-indexed
-            character: span.col.saturating_sub(1) as u32,
+            line: range.line as u32,
+            character: range.start as u32,
         },
         end: Position {
-            line: span.line.saturating_sub(1) as u32,
-            character: (span.col + (span.end - span.start)).saturating_sub(1) as u32,
+            line: range.line as u32,
+            character: range.end as u32,
         },
     }
 }
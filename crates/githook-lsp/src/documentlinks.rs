@@ -1,43 +1,86 @@
 use tower_lsp::lsp_types::*;
+use githook_syntax::Statement;
+use githook_eval::package_resolver::resolve_package_path;
 use crate::document::DocumentState;
-use crate::import_resolver::resolve_import_path;
+use crate::import_resolver::{path_to_uri, resolve_import_path};
 
-/// Get document links for import statements
+/// Get document links for import and use statements
 pub fn get_document_links(doc: &DocumentState, current_uri: &str) -> Vec<DocumentLink> {
     let mut links = Vec::new();
-    
-    for (_, import_path) in &doc.imports {
-        // Find the import statement in the text
-        let import_text = format!("\"{}\"", import_path);
-        
-        for (line_num, line) in doc.text.lines().enumerate() {
-            if let Some(pos) = line.find(&import_text) {
-                // Resolve the import path to absolute path
-                if let Some(resolved) = resolve_import_path(current_uri, import_path) {
-                    let file_uri = format!("file://{}", resolved.to_string_lossy());
-                    
-                    if let Ok(uri) = Url::parse(&file_uri) {
-                        links.push(DocumentLink {
-                            range: Range {
-                                start: Position {
-                                    line: line_num as u32,
-                                    character: (pos + 1) as u32, // Skip opening quote
-                                },
-                                end: Position {
-                                    line: line_num as u32,
-                                    character: (pos + import_path.len() + 1) as u32,
-                                },
-                            },
-                            target: Some(uri),
-                            tooltip: Some(format!("Open {}", import_path)),
-                            data: None,
-                        });
+
+    let Some(statements) = &doc.ast else {
+        return links;
+    };
+
+    for stmt in statements {
+        match stmt {
+            Statement::Import { path, span, .. } => {
+                if let Some(resolved) = resolve_import_path(current_uri, path) {
+                    push_link(doc, span.line, path, &path_to_uri(&resolved), &mut links);
+                }
+            }
+            Statement::Use { namespace, name, span, .. } => {
+                // Only link packages that are already installed or cached
+                if let Ok(resolved) = resolve_package_path(namespace, name) {
+                    if resolved.exists() {
+                        let spec = format!("@{}/{}", namespace, name);
+                        push_link(doc, span.line, &spec, &path_to_uri(&resolved), &mut links);
                     }
                 }
-                break;
             }
+            _ => {}
         }
     }
-    
+
     links
 }
+
+/// Link the quoted `target_text` found on `line` (1-based) to `target_uri`
+fn push_link(doc: &DocumentState, line: usize, target_text: &str, target_uri: &str, links: &mut Vec<DocumentLink>) {
+    let quoted = format!("\"{}\"", target_text);
+
+    let Some(line_text) = doc.text.lines().nth(line - 1) else {
+        return;
+    };
+    let Some(pos) = line_text.find(&quoted) else {
+        return;
+    };
+    let Ok(uri) = Url::parse(target_uri) else {
+        return;
+    };
+
+    links.push(DocumentLink {
+        range: Range {
+            start: Position {
+                line: (line - 1) as u32,
+                character: (pos + 1) as u32, // Skip opening quote
+            },
+            end: Position {
+                line: (line - 1) as u32,
+                character: (pos + target_text.len() + 1) as u32,
+            },
+        },
+        target: Some(uri),
+        tooltip: Some(format!("Open {}", target_text)),
+        data: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_links_to_the_resolved_file() {
+        let uri = "file:///repo/hooks/pre-commit.ghook";
+        let doc = DocumentState::new("run \"a\"\nimport \"./shared/lint.ghook\" as lint\n".to_string(), Some(uri));
+
+        let links = get_document_links(&doc, uri);
+        assert_eq!(links.len(), 1);
+
+        let link = &links[0];
+        assert_eq!(link.target.as_ref().unwrap().as_str(), "file:///repo/hooks/shared/lint.ghook");
+        assert_eq!(link.range.start, Position { line: 1, character: 8 });
+        assert_eq!(link.range.end, Position { line: 1, character: 27 });
+    }
+}
@@ -1,104 +1,108 @@
 use tower_lsp::lsp_types::*;
 use githook_syntax::Statement;
+use githook_syntax::error::Span;
 
 /// Get folding ranges for code blocks
-pub fn get_folding_ranges(ast: &Option<Vec<Statement>>) -> Vec<FoldingRange> {
+pub fn get_folding_ranges(ast: &Option<Vec<Statement>>, text: &str) -> Vec<FoldingRange> {
     let mut ranges = Vec::new();
-    
+
     if let Some(statements) = ast {
         for stmt in statements {
-            collect_folding_ranges(stmt, &mut ranges);
+            collect_folding_ranges(stmt, text, &mut ranges);
         }
     }
-    
+
     ranges
 }
 
-fn collect_folding_ranges(stmt: &Statement, ranges: &mut Vec<FoldingRange>) {
+fn collect_folding_ranges(stmt: &Statement, text: &str, ranges: &mut Vec<FoldingRange>) {
     match stmt {
         Statement::MacroDefinition { span, body, .. } => {
-            if !body.is_empty() {
-                // Find the end line by getting the max span from body
-                let end_line = body.iter()
-                    .filter_map(|s| get_statement_span(s))
-                    .map(|s| s.line)
-                    .max()
-                    .unwrap_or(span.line);
-                
-                ranges.push(FoldingRange {
-                    start_line: (span.line - 1) as u32,
-                    start_character: None,
-                    end_line: (end_line - 1) as u32,
-                    end_character: None,
-                    kind: Some(FoldingRangeKind::Region),
-                    collapsed_text: None,
-                });
-            }
-            
-            // Recursively fold inner blocks
-            for inner_stmt in body {
-                collect_folding_ranges(inner_stmt, ranges);
-            }
+            push_block_range(span, text, ranges);
+            collect_body(body, text, ranges);
         }
         Statement::When { span, body, else_body, .. } => {
-            if !body.is_empty() {
-                let end_line = body.iter()
-                    .filter_map(|s| get_statement_span(s))
-                    .map(|s| s.line)
-                    .max()
-                    .unwrap_or(span.line);
-                
-                ranges.push(FoldingRange {
-                    start_line: (span.line - 1) as u32,
-                    start_character: None,
-                    end_line: (end_line - 1) as u32,
-                    end_character: None,
-                    kind: Some(FoldingRangeKind::Region),
-                    collapsed_text: None,
-                });
-            }
-            
-            for inner_stmt in body {
-                collect_folding_ranges(inner_stmt, ranges);
-            }
-            
+            push_block_range(span, text, ranges);
+            collect_body(body, text, ranges);
+
             if let Some(else_stmts) = else_body {
-                for inner_stmt in else_stmts {
-                    collect_folding_ranges(inner_stmt, ranges);
-                }
+                collect_body(else_stmts, text, ranges);
             }
         }
         Statement::Group { span, definition, .. } => {
-            if !definition.body.is_empty() {
-                let end_line = definition.body.iter()
-                    .filter_map(|s| get_statement_span(s))
-                    .map(|s| s.line)
-                    .max()
-                    .unwrap_or(span.line);
-                
-                ranges.push(FoldingRange {
-                    start_line: (span.line - 1) as u32,
-                    start_character: None,
-                    end_line: (end_line - 1) as u32,
-                    end_character: None,
-                    kind: Some(FoldingRangeKind::Region),
-                    collapsed_text: None,
-                });
+            push_block_range(span, text, ranges);
+            collect_body(&definition.body, text, ranges);
+        }
+        Statement::ForEachStringList { span, body, .. }
+        | Statement::ForEachArray { span, body, .. }
+        | Statement::ForEachStagedFiles { span, body, .. }
+        | Statement::StagedFiles { span, body, .. }
+        | Statement::StagedContentForeach { span, body, .. }
+        | Statement::AllFiles { span, body, .. } => {
+            push_block_range(span, text, ranges);
+            collect_body(body, text, ranges);
+        }
+        Statement::Match { span, arms, .. } => {
+            push_block_range(span, text, ranges);
+            for arm in arms {
+                collect_body(&arm.action, text, ranges);
             }
         }
         _ => {}
     }
 }
 
-fn get_statement_span(stmt: &Statement) -> Option<githook_syntax::error::Span> {
-    match stmt {
-        Statement::Run(_, span) => Some(*span),
-        Statement::Block(_, span) => Some(*span),
-        Statement::MacroDefinition { span, .. } => Some(*span),
-        Statement::MacroCall { span, .. } => Some(*span),
-        Statement::When { span, .. } => Some(*span),
-        Statement::Group { span, .. } => Some(*span),
-        Statement::Import { span, .. } => Some(*span),
-        _ => None,
+fn collect_body(body: &[Statement], text: &str, ranges: &mut Vec<FoldingRange>) {
+    for inner_stmt in body {
+        collect_folding_ranges(inner_stmt, text, ranges);
+    }
+}
+
+/// Folds from the line the block starts on to the line of its closing brace
+fn push_block_range(span: &Span, text: &str, ranges: &mut Vec<FoldingRange>) {
+    let start_line = span.line.saturating_sub(1);
+    let end_offset = span.end.min(text.len());
+    let end_line = text.as_bytes()[..end_offset]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count();
+
+    if end_line <= start_line {
+        return;
+    }
+
+    ranges.push(FoldingRange {
+        start_line: start_line as u32,
+        start_character: None,
+        end_line: end_line as u32,
+        end_character: None,
+        kind: Some(FoldingRangeKind::Region),
+        collapsed_text: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DocumentState;
+
+    fn line_ranges(text: &str) -> Vec<(u32, u32)> {
+        let doc = DocumentState::new(text.to_string(), None);
+        get_folding_ranges(&doc.ast, text)
+            .iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn group_folds_to_its_closing_brace() {
+        let text = "# checks\ngroup lint {\n  run \"cargo fmt\"\n  run \"cargo clippy\"\n}\nrun \"done\"\n";
+        assert_eq!(line_ranges(text), vec![(1, 4)]);
+    }
+
+    #[test]
+    fn nested_blocks_fold_separately() {
+        let text = "group lint {\n  foreach file in staged_files matching \"*.rs\" {\n    run \"rustfmt {file}\"\n  }\n}\n";
+        assert_eq!(line_ranges(text), vec![(0, 4), (1, 3)]);
     }
 }
@@ -131,16 +131,18 @@ fn create_hover(markdown: &str) -> Hover {
 
 fn get_keyword_documentation(keyword: &str) -> Option<&'static str> {
     match keyword {
-        "run" => Some("**run** `\"command\"`\n\nExecute a shell command.\n\n**Example:**\n```githook\nrun \"cargo test\"\nrun \"npm run lint\"\n```"),
+        "run" => Some("**run** `\"command\"` [**in** `\"dir\"`] [**env** **{** `\"KEY\": \"value\"` **}**] [**retry** `n` [**backoff** `secs`]]\n\nExecute a shell command, optionally in another directory, with extra environment variables and retrying it up to `n` times on failure.\n\n**Example:**\n```githook\nrun \"cargo test\"\nrun \"cargo test\" in \"crates/foo\"\nrun \"cargo build\" env { \"RUSTFLAGS\": \"-D warnings\" }\nrun \"npm ci\" retry 3 backoff 2\n```"),
         "block" => Some("**block** `\"message\"`\n\nBlock the commit with a message.\n\n**Example:**\n```githook\nblock \"Direct commits not allowed\"\n```"),
         "block_if" => Some("**block_if** `<condition>` **message** `\"text\"`\n\nBlock commit if condition is true.\n\n**Example:**\n```githook\nblock_if branch_name == \"main\" message \"No commits to main\"\nblock_if file_size > 1000000 message \"File too large\"\n```"),
         "warn_if" => Some("**warn_if** `<condition>` **message** `\"text\"`\n\nWarn if condition is true (non-blocking).\n\n**Example:**\n```githook\nwarn_if modified_lines > 500 message \"Large changeset\"\n```"),
         "when" => Some("**when** `<condition>` **{** ... **}**\n\nConditional execution block.\n\n**Example:**\n```githook\nwhen branch_name == \"main\" {\n    run \"npm test\"\n    block_if content matches \"TODO\"\n}\n```"),
         "foreach" => Some("**foreach** `file` **in** `<collection>` **matching** `\"pattern\"` **{** ... **}**\n\nIterate over files.\n\n**Example:**\n```githook\nforeach file in staged_files matching \"*.rs\" {\n    block_if content matches \"panic!\"\n}\n```"),
-        "match" => Some("**match** `<value>` **{** ... **}**\n\nPattern matching.\n\n**Example:**\n```githook\nmatch file {\n    \"*.rs\" -> run \"cargo clippy\"\n    \"*.js\" -> run \"npm run lint\"\n    _ -> run \"echo 'unknown'\"\n}\n```"),
+        "match" => Some("**match** `file|content|diff|branch` **{** ... **}**\n\nPattern matching. Inside an arm, `{matched}` holds the matched value.\n\n**Example:**\n```githook\nmatch file {\n    \"*.rs\" -> run \"cargo clippy {matched}\"\n    \"*.js\" -> run \"npm run lint\"\n    _ -> run \"echo 'unknown'\"\n}\n```"),
         "macro" => Some("**macro** `name` **{** ... **}**\n\nDefine a reusable macro.\n\n**Example:**\n```githook\nmacro check_main {\n    block_if branch_name == \"main\"\n}\n\n@check_main  # Call the macro\n```"),
         "let" => Some("**let** `name` **=** `[...]`\n\nDefine a variable (string list).\n\n**Example:**\n```githook\nlet forbidden = [\".txt\", \".zip\"]\n\nforeach file in staged_files {\n    block_if {file:extension} in {forbidden}\n}\n```"),
         "use" => Some("**use** `@namespace/package`\n\nImport from remote package (GitHub).\n\n**Example:**\n```githook\nuse @preview/security\n\n@no_secrets\n```"),
+        "ignorecase" => Some("**ignorecase**\n\nMake the preceding `matches` regex case-insensitive.\n\n**Example:**\n```githook\nblock_if commit_message matches \"^wip\" ignorecase\n```"),
+        "require" => Some("**require clean**\n\nBlock unless the working tree has no unstaged or untracked changes.\n\n**Example:**\n```githook\nrequire clean\n```"),
         "import" => Some("**import** `\"path/to/file.ghook\"`\n\nImport from local file.\n\n**Example:**\n```githook\nimport \"./common.ghook\"\n```"),
         _ => None,
     }
@@ -152,17 +154,33 @@ fn get_property_documentation(property: &str) -> Option<&'static str> {
         "content" => Some("**content**: String\n\nStaged file content.\n\n**Example:**\n```githook\nblock_if content matches \"TODO\"\nblock_if content contains \"panic!\"\n```"),
         "staged_content" => Some("**staged_content**: String\n\nStaged file content (alias for content).\n\n**Example:**\n```githook\nblock_if staged_content matches \"console.log\"\n```"),
         "diff" => Some("**diff**: String\n\nStaged changes diff.\n\n**Example:**\n```githook\nblock_if diff matches \"^-.*password\"\n```"),
+        "added_lines" => Some("**added_lines**: String\n\nAdded lines of the staged diff (whole diff outside foreach).\n\n**Example:**\n```githook\nblock_if added_lines contains \"dbg!\"\n```"),
         "commit_message" => Some("**commit_message**: String\n\nCommit message text.\n\n**Example:**\n```githook\nblock_if commit_message contains \"WIP\"\n```"),
         "file_size" => Some("**file_size**: Number\n\nFile size in bytes.\n\n**Example:**\n```githook\nblock_if file_size > 1048576 message \"File > 1MB\"\n```"),
         "modified_lines" => Some("**modified_lines**: Number\n\nChanged lines in diff.\n\n**Example:**\n```githook\nwarn_if modified_lines > 500\n```"),
         "files_changed" => Some("**files_changed**: Number\n\nNumber of changed files.\n\n**Example:**\n```githook\nwarn_if files_changed > 20\n```"),
-        "additions" => Some("**additions**: Number\n\nAdded lines.\n\n**Example:**\n```githook\nblock_if additions > 1000\n```"),
+        "staged_count" => Some("**staged_count**: Number\n\nNumber of staged files (added, copied or modified).\n\n**Example:**\n```githook\nblock_if staged_count > 50 message \"Too many files\"\n```"),
+        "additions" => Some("**additions**: Number\n\nAdded lines, optionally only in files `matching` a glob.\n\n**Example:**\n```githook\nblock_if additions > 1000\nwarn_if additions matching \"*.rs\" > 300\n```"),
         "deletions" => Some("**deletions**: Number\n\nDeleted lines.\n\n**Example:**\n```githook\nblock_if deletions > 500\n```"),
+        "subject_length" => Some("**subject_length**: Number\n\nCommit subject length in characters.\n\n**Example:**\n```githook\nblock_if subject_length > 72\n```"),
+        "max_line_length" => Some("**max_line_length**: Number\n\nLength of the longest commit body line (comment lines ignored).\n\n**Example:**\n```githook\nwarn_if max_line_length > 100\n```"),
         "commits_ahead" => Some("**commits_ahead**: Number\n\nCommits ahead of remote.\n\n**Example:**\n```githook\nblock_if commits_ahead > 5\n```"),
+        "is_binary" => Some("**is_binary**: Boolean\n\nThe current staged file is binary (inside foreach).\n\n**Example:**\n```githook\nforeach f in staged_files matching \"*\" {\n  block_if is_binary message \"No binaries\"\n}\n```"),
+        "merge_in_progress" => Some("**merge_in_progress**: Boolean\n\nA merge has been started and not yet committed.\n\n**Example:**\n```githook\nwhen merge_in_progress {\n    run \"cargo test\"\n}\n```"),
+        "has_conflicts" => Some("**has_conflicts**: Boolean\n\nSome files still have unresolved merge conflicts.\n\n**Example:**\n```githook\nblock_if has_conflicts message \"Resolve conflicts first\"\n```"),
+        "worktree_dirty" => Some("**worktree_dirty**: Boolean\n\nThe working tree has unstaged or untracked (not ignored) changes.\n\n**Example:**\n```githook\nblock_if worktree_dirty message \"Commit or stash your changes first\"\n```"),
+        "empty_commit" => Some("**empty_commit**: Boolean\n\nNothing is staged, e.g. `git commit --allow-empty`.\n\n**Example:**\n```githook\nwhen not empty_commit {\n    run \"cargo test\"\n}\n```"),
+        "tag_exists" => Some("**tag_exists** `\"name\"`: Boolean\n\nA tag with this name exists.\n\n**Example:**\n```githook\nblock_if tag_exists \"v1.0.0\" message \"Version already released\"\n```"),
+        "any_binary" => Some("**any_binary**: Boolean\n\nAt least one staged file is binary.\n\n**Example:**\n```githook\nwarn_if any_binary message \"Binary files staged\"\n```"),
         "author_set" => Some("**author_set**: Boolean\n\nGit user.name is configured.\n\n**Example:**\n```githook\nblock_if not author_set message \"Configure git user\"\n```"),
         "author_email_set" => Some("**author_email_set**: Boolean\n\nGit user.email is configured.\n\n**Example:**\n```githook\nblock_if not author_email_set\n```"),
         "contains_secrets" => Some("**contains_secrets**: Boolean\n\nSecrets/credentials detected.\n\n**Example:**\n```githook\nblock_if contains_secrets message \"Secrets found!\"\n```"),
         "staged_files" => Some("**staged_files**: File Collection\n\nAll staged files (for foreach).\n\n**Example:**\n```githook\nforeach file in staged_files matching \"*.rs\" {\n    block_if content matches \"panic!\"\n}\n```"),
+        "added_files" => Some("**added_files**: File Collection\n\nStaged files that are newly added (for foreach).\n\n**Example:**\n```githook\nforeach file in added_files where file_size > 1048576 {\n    block \"Large new file: {file}\"\n}\n```"),
+        "deleted_files" => Some("**deleted_files**: File Collection\n\nFiles whose deletion is staged (for foreach).\n\n**Example:**\n```githook\nforeach file in deleted_files matching \"migrations/*\" {\n    block \"Do not delete migrations\"\n}\n```"),
+        "changed_since" => Some("**changed_since** `\"<ref>\"`: File Collection\n\nFiles changed between a git ref and the working tree (for foreach).\n\n**Example:**\n```githook\nforeach file in changed_since \"origin/main\" matching \"*.rs\" {\n    run \"rustfmt --check {file}\"\n}\n```"),
+        "conflict_files" => Some("**conflict_files**: File Collection\n\nFiles with unresolved merge conflicts (for foreach).\n\n**Example:**\n```githook\nforeach file in conflict_files {\n    block \"Unresolved conflict in {file}\"\n}\n```"),
+        "commit_files" => Some("**commit_files**: File Collection\n\nFiles touched by the last commit, for post-commit hooks (for foreach).\n\n**Example:**\n```githook\nforeach file in commit_files matching \"*.md\" {\n    run \"echo updated {file}\"\n}\n```"),
         "all_files" => Some("**all_files**: File Collection\n\nAll files in repo (for foreach).\n\n**Example:**\n```githook\nforeach file in all_files matching \"*.md\" {\n    warn_if file_size > 100000\n}\n```"),
         _ => None,
     }
@@ -173,8 +191,11 @@ fn format_macro_body(body: &[githook_syntax::Statement]) -> String {
     let mut result = String::new();
     for stmt in body {
         let line = match stmt {
-            githook_syntax::Statement::Run(cmd, _) => format!("    run \"{}\"", cmd),
+            githook_syntax::Statement::Run { command, fixes, .. } => {
+                format!("    run \"{}\"{}", command, if *fixes { " fixes" } else { "" })
+            }
             githook_syntax::Statement::Block(msg, _) => format!("    block \"{}\"", msg),
+            githook_syntax::Statement::Comment { text, .. } => format!("    {}", text),
             githook_syntax::Statement::ConditionalRule { severity, message, .. } => {
                 let action_str = match severity {
                     githook_syntax::RuleSeverity::Block(_) => "block_if",
@@ -0,0 +1,114 @@
+use tower_lsp::lsp_types::*;
+use githook_syntax::{tokenize_with_spans, Span, SpannedToken, Statement, Token};
+
+/// Get type hints for `let` bindings
+pub fn get_inlay_hints(ast: &Option<Vec<Statement>>, text: &str, range: Range) -> Vec<InlayHint> {
+    let mut hints = Vec::new();
+    let tokens = tokenize_with_spans(text).unwrap_or_default();
+
+    if let Some(statements) = ast {
+        for stmt in statements {
+            collect_inlay_hints(stmt, &tokens, &mut hints);
+        }
+    }
+
+    hints.retain(|hint| hint.position.line >= range.start.line && hint.position.line <= range.end.line);
+    hints
+}
+
+fn collect_inlay_hints(stmt: &Statement, tokens: &[SpannedToken], hints: &mut Vec<InlayHint>) {
+    match stmt {
+        Statement::LetStringList { name, items, span } => {
+            // `let` is only ever bound to a string list: "let <name> = [...]"
+            let Some(name_span) = let_name_span(tokens, span, name) else {
+                return;
+            };
+            hints.push(InlayHint {
+                position: Position {
+                    line: (name_span.line - 1) as u32,
+                    character: (name_span.col - 1 + name.len()) as u32,
+                },
+                label: InlayHintLabel::String(": array".to_string()),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: Some(InlayHintTooltip::String(format!("{} string items", items.len()))),
+                padding_left: Some(false),
+                padding_right: Some(true),
+                data: None,
+            });
+        }
+        Statement::MacroDefinition { body, .. }
+        | Statement::ForEachStringList { body, .. }
+        | Statement::ForEachArray { body, .. }
+        | Statement::ForEachStagedFiles { body, .. }
+        | Statement::StagedFiles { body, .. }
+        | Statement::StagedContentForeach { body, .. }
+        | Statement::AllFiles { body, .. } => {
+            for inner_stmt in body {
+                collect_inlay_hints(inner_stmt, tokens, hints);
+            }
+        }
+        Statement::When { body, else_body, .. } => {
+            for inner_stmt in body.iter().chain(else_body.iter().flatten()) {
+                collect_inlay_hints(inner_stmt, tokens, hints);
+            }
+        }
+        Statement::Group { definition, .. } => {
+            for inner_stmt in &definition.body {
+                collect_inlay_hints(inner_stmt, tokens, hints);
+            }
+        }
+        Statement::Match { arms, .. } => {
+            for arm in arms {
+                for inner_stmt in &arm.action {
+                    collect_inlay_hints(inner_stmt, tokens, hints);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Span of the identifier that follows the `let` keyword starting at `let_span`
+fn let_name_span(tokens: &[SpannedToken], let_span: &Span, name: &str) -> Option<Span> {
+    let pos = tokens.iter().position(|st| {
+        st.token == Token::Let && st.span.line == let_span.line && st.span.col == let_span.col
+    })?;
+    match tokens.get(pos + 1) {
+        Some(SpannedToken { token: Token::Identifier(ident), span }) if ident == name => Some(*span),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DocumentState;
+
+    fn hints_for(text: &str) -> Vec<(Position, String)> {
+        let doc = DocumentState::new(text.to_string(), None);
+        let range = Range {
+            start: Position { line: 0, character: 0 },
+            end: Position { line: u32::MAX, character: 0 },
+        };
+        get_inlay_hints(&doc.ast, text, range)
+            .into_iter()
+            .map(|hint| match hint.label {
+                InlayHintLabel::String(label) => (hint.position, label),
+                InlayHintLabel::LabelParts(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hint_follows_the_identifier() {
+        let text = "let   exts = [\"rs\", \"toml\"]\ngroup checks {\n    let dirs = [\"src\"]\n}\n";
+        assert_eq!(
+            hints_for(text),
+            vec![
+                (Position { line: 0, character: 10 }, ": array".to_string()),
+                (Position { line: 2, character: 12 }, ": array".to_string()),
+            ]
+        );
+    }
+}
@@ -15,6 +15,7 @@ mod folding;
 mod codelens;
 mod semantic_tokens;
 mod documentlinks;
+mod inlay_hints;
 
 use backend::GithookLanguageServer;
 
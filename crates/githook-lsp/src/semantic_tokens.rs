@@ -32,7 +32,7 @@ pub fn get_semantic_tokens(ast: &Option<Vec<Statement>>, text: &str) -> Semantic
     // Collect tokens from AST
     if let Some(statements) = ast {
         for stmt in statements {
-            collect_tokens_raw(stmt, &mut tokens);
+            collect_tokens_raw(stmt, text, &mut tokens);
         }
     }
     
@@ -145,7 +145,7 @@ fn collect_comments_raw(text: &str, tokens: &mut Vec<RawToken>) {
     }
 }
 
-fn collect_tokens_raw(stmt: &Statement, tokens: &mut Vec<RawToken>) {
+fn collect_tokens_raw(stmt: &Statement, text: &str, tokens: &mut Vec<RawToken>) {
     match stmt {
         Statement::MacroDefinition { name, span, body, .. } => {
             // "macro" keyword (type 0)
@@ -157,17 +157,26 @@ fn collect_tokens_raw(stmt: &Statement, tokens: &mut Vec<RawToken>) {
                 modifiers: 0,
             });
             
-            // Macro name (type 1 = function, modifier 2 = definition)
-            tokens.push(RawToken {
-                line: (span.line - 1) as u32,
-                start: (span.col + 6) as u32,
-                length: name.len() as u32,
-                token_type: 1,
-                modifiers: 2,
-            });
+            // Macro name (type 1 = function, modifier 2 = definition), after the keyword
+            let keyword_end = span.col - 1 + 5;
+            let pos = text
+                .lines()
+                .nth(span.line - 1)
+                .and_then(|line_text| line_text.get(keyword_end..))
+                .and_then(|rest| rest.find(name.as_str()))
+                .map(|offset| keyword_end + offset);
+            if let Some(pos) = pos {
+                tokens.push(RawToken {
+                    line: (span.line - 1) as u32,
+                    start: pos as u32,
+                    length: name.len() as u32,
+                    token_type: 1,
+                    modifiers: 2,
+                });
+            }
             
             for inner_stmt in body {
-                collect_tokens_raw(inner_stmt, tokens);
+                collect_tokens_raw(inner_stmt, text, tokens);
             }
         }
         Statement::MacroCall { name, namespace, span, .. } => {
@@ -199,7 +208,7 @@ fn collect_tokens_raw(stmt: &Statement, tokens: &mut Vec<RawToken>) {
                 });
             }
         }
-        Statement::Run(_, span) => {
+        Statement::Run { span, .. } => {
             // "run" keyword
             tokens.push(RawToken {
                 line: (span.line - 1) as u32,
@@ -209,13 +218,71 @@ fn collect_tokens_raw(stmt: &Statement, tokens: &mut Vec<RawToken>) {
                 modifiers: 0,
             });
         }
+        Statement::Use { namespace, name, span, .. } => {
+            // "use" keyword
+            tokens.push(RawToken {
+                line: (span.line - 1) as u32,
+                start: (span.col - 1) as u32,
+                length: 3,
+                token_type: 0,
+                modifiers: 0,
+            });
+            // Package specifier "@ns/name" (type 6 = namespace), inside the quotes
+            let spec = format!("@{}/{}", namespace, name);
+            let quoted = format!("\"{}\"", spec);
+            let pos = text
+                .lines()
+                .nth(span.line - 1)
+                .and_then(|line_text| line_text.find(&quoted));
+            if let Some(pos) = pos {
+                tokens.push(RawToken {
+                    line: (span.line - 1) as u32,
+                    start: (pos + 1) as u32, // Skip opening quote
+                    length: spec.len() as u32,
+                    token_type: 6,
+                    modifiers: 0,
+                });
+            }
+        }
+        Statement::Import { span, .. } => {
+            // "import" keyword
+            tokens.push(RawToken {
+                line: (span.line - 1) as u32,
+                start: (span.col - 1) as u32,
+                length: 6,
+                token_type: 0,
+                modifiers: 0,
+            });
+        }
         Statement::When { body, else_body, .. } => {
             for inner_stmt in body {
-                collect_tokens_raw(inner_stmt, tokens);
+                collect_tokens_raw(inner_stmt, text, tokens);
             }
             if let Some(else_stmts) = else_body {
                 for inner_stmt in else_stmts {
-                    collect_tokens_raw(inner_stmt, tokens);
+                    collect_tokens_raw(inner_stmt, text, tokens);
+                }
+            }
+        }
+        Statement::Group { definition, .. } => {
+            for inner_stmt in &definition.body {
+                collect_tokens_raw(inner_stmt, text, tokens);
+            }
+        }
+        Statement::ForEachStringList { body, .. }
+        | Statement::ForEachArray { body, .. }
+        | Statement::ForEachStagedFiles { body, .. }
+        | Statement::StagedFiles { body, .. }
+        | Statement::StagedContentForeach { body, .. }
+        | Statement::AllFiles { body, .. } => {
+            for inner_stmt in body {
+                collect_tokens_raw(inner_stmt, text, tokens);
+            }
+        }
+        Statement::Match { arms, .. } => {
+            for arm in arms {
+                for inner_stmt in &arm.action {
+                    collect_tokens_raw(inner_stmt, text, tokens);
                 }
             }
         }
@@ -265,3 +332,62 @@ impl SemanticTokensBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DocumentState;
+
+    /// Decodes the delta encoding into (line, start, length, token_type).
+    fn absolute_tokens(text: &str) -> Vec<(u32, u32, u32, u32)> {
+        let doc = DocumentState::new(text.to_string(), None);
+        let mut line = 0;
+        let mut start = 0;
+        get_semantic_tokens(&doc.ast, text)
+            .data
+            .iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    start = 0;
+                }
+                line += token.delta_line;
+                start += token.delta_start;
+                (line, start, token.length, token.token_type)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn use_specifier_is_found_inside_the_quotes() {
+        let text = "use   \"@acme/lint\" as lint\nrun \"echo\"\n";
+        assert_eq!(
+            absolute_tokens(text),
+            vec![(0, 0, 3, 0), (0, 7, 10, 6), (1, 0, 3, 0)]
+        );
+    }
+
+    #[test]
+    fn comments_and_keywords_have_their_ranges() {
+        let text = "# check\nrun \"a\"  # trailing\n";
+        assert_eq!(
+            absolute_tokens(text),
+            vec![(0, 0, 7, 8), (1, 0, 3, 0), (1, 9, 10, 8)]
+        );
+    }
+
+    #[test]
+    fn macro_definition_and_call_are_functions() {
+        let text = "macro  fmt {\n  run \"cargo fmt\"\n}\n@fmt\n@std:lint\n";
+        assert_eq!(
+            absolute_tokens(text),
+            vec![
+                (0, 0, 5, 0),
+                (0, 7, 3, 1),
+                (1, 2, 3, 0),
+                (3, 1, 3, 1),
+                (4, 1, 3, 6),
+                (4, 5, 4, 1),
+            ]
+        );
+    }
+}
@@ -2,18 +2,37 @@ use crate::error::Span;
 
 #[derive(Debug, Clone)]
 pub enum Statement {
-    Run(String, Span),
+    Run {
+        command: String,
+        /// Directory to run the command in, from `in "dir"`
+        cwd: Option<String>,
+        /// Variables from `env { "KEY": "value" }`, in source order
+        env: Vec<(String, String)>,
+        fixes: bool,
+        retries: usize,
+        backoff: f64,
+        span: Span,
+    },
+    /// A `#` or `/* */` comment in statement position, kept for the formatter.
+    /// `trailing` comments follow a statement on the same line.
+    Comment {
+        text: String,
+        trailing: bool,
+        span: Span,
+    },
     Block(String, Span),
     BoolLiteral(bool, Span),
     Parallel {
         commands: Vec<String>,
+        pattern: Option<String>,
         span: Span,
     },
 
     LetStringList { name: String, items: Vec<String>, span: Span },
+    Assign { name: String, items: Vec<String>, span: Span },
     ForEachStringList { var: String, list: String, body: Vec<Statement>, span: Span },
     ForEachArray { var: String, items: Vec<Argument>, body: Vec<Statement>, span: Span },
-    ForEachStagedFiles { var: String, pattern: String, where_cond: Option<BlockCondition>, body: Vec<Statement>, span: Span },
+    ForEachStagedFiles { var: String, source: FileSource, pattern: String, where_cond: Option<BlockCondition>, body: Vec<Statement>, span: Span },
     MacroDefinition {
         name: String,
         params: Vec<String>,
@@ -55,6 +74,8 @@ pub enum Statement {
         span: Span,
     },
     AllowCommand(String, Span),
+    /// `require clean`: block unless the working tree has no unstaged or untracked changes
+    RequireClean(Span),
     AllFiles {
         pattern: String,
         body: Vec<Statement>,
@@ -97,11 +118,39 @@ pub enum Statement {
     }
 }
 
+/// File collection a `foreach` iterates over
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileSource {
+    Staged,
+    Added,
+    Deleted,
+    /// Files changed between a git ref and the working tree
+    ChangedSince(String),
+    /// Files touched by the `HEAD` commit, for post-commit hooks
+    CommitFiles,
+    /// Files with unresolved merge conflicts
+    ConflictFiles,
+}
+
+impl std::fmt::Display for FileSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileSource::Staged => write!(f, "staged_files"),
+            FileSource::Added => write!(f, "added_files"),
+            FileSource::Deleted => write!(f, "deleted_files"),
+            FileSource::ChangedSince(git_ref) => write!(f, "changed_since \"{}\"", git_ref),
+            FileSource::CommitFiles => write!(f, "commit_files"),
+            FileSource::ConflictFiles => write!(f, "conflict_files"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MatchSubject {
     File(Span),
     Content(Span),
     Diff(Span),
+    Branch(Span),
 }
 
 #[derive(Debug, Clone)]
@@ -190,16 +239,20 @@ pub enum PropertyValue {
     BranchName(Span),
     Content(Span),
     Diff(Span),
+    AddedLines(Span),
     CommitMessage(Span),
     Extension(Span),
     Filename(Span),
     Basename(Span),
     Dirname(Span),
-    ModifiedLines(Span),
-    FilesChanged(Span),
-    Additions(Span),
-    Deletions(Span),
+    ModifiedLines(Option<String>, Span),
+    FilesChanged(Option<String>, Span),
+    StagedCount(Span),
+    Additions(Option<String>, Span),
+    Deletions(Option<String>, Span),
     CommitsAhead(Span),
+    SubjectLength(Span),
+    MaxLineLength(Span),
     EnvVar(String, Span),
     Placeholder(String, Span),
 }
@@ -230,7 +283,16 @@ pub enum BlockCondition {
     AuthorSet(Span),
     AuthorEmailSet(Span),
     AuthorMissing(Span),
+    IsBinary(Span),
+    AnyBinary(Span),
+    MergeInProgress(Span),
+    HasConflicts(Span),
+    TagExists(String, Span),
+    EmptyCommit(Span),
+    WorktreeDirty(Span),
     EnvEquals(String, String, Span),
+    EnvNotEquals(String, String, Span),
+    EnvContains(String, String, Span),
     
     MacroCall { name: String, args: Vec<Argument>, span: Span },
     NotMacroCall { name: String, args: Vec<Argument>, span: Span },
@@ -250,16 +312,20 @@ impl BlockCondition {
                     PropertyValue::BranchName(_) => "branch name".to_string(),
                     PropertyValue::Content(_) => "content".to_string(),
                     PropertyValue::Diff(_) => "diff".to_string(),
+                    PropertyValue::AddedLines(_) => "added lines".to_string(),
                     PropertyValue::CommitMessage(_) => "commit message".to_string(),
                     PropertyValue::Extension(_) => "extension".to_string(),
                     PropertyValue::Filename(_) => "filename".to_string(),
                     PropertyValue::Basename(_) => "basename".to_string(),
                     PropertyValue::Dirname(_) => "dirname".to_string(),
-                    PropertyValue::ModifiedLines(_) => "modified lines".to_string(),
-                    PropertyValue::FilesChanged(_) => "files changed".to_string(),
-                    PropertyValue::Additions(_) => "additions".to_string(),
-                    PropertyValue::Deletions(_) => "deletions".to_string(),
+                    PropertyValue::ModifiedLines(pattern, _) => scoped_stat("modified lines", pattern),
+                    PropertyValue::FilesChanged(pattern, _) => scoped_stat("files changed", pattern),
+                    PropertyValue::StagedCount(_) => "staged file count".to_string(),
+                    PropertyValue::Additions(pattern, _) => scoped_stat("additions", pattern),
+                    PropertyValue::Deletions(pattern, _) => scoped_stat("deletions", pattern),
                     PropertyValue::CommitsAhead(_) => "commits ahead".to_string(),
+                    PropertyValue::SubjectLength(_) => "subject length".to_string(),
+                    PropertyValue::MaxLineLength(_) => "max line length".to_string(),
                     PropertyValue::EnvVar(key, _) => format!("env:{}", key),
                     PropertyValue::Placeholder(p, _) => format!("{{{}}}", p),
                 };
@@ -297,9 +363,22 @@ impl BlockCondition {
             BlockCondition::AuthorMissing(_) => "Git author is missing".into(),
             BlockCondition::AuthorSet(_) => "Git author must be set".into(),
             BlockCondition::AuthorEmailSet(_) => "Git author email must be set".into(),
+            BlockCondition::IsBinary(_) => "File is binary".into(),
+            BlockCondition::AnyBinary(_) => "Binary files are staged".into(),
+            BlockCondition::MergeInProgress(_) => "A merge is in progress".into(),
+            BlockCondition::HasConflicts(_) => "Unresolved merge conflicts".into(),
+            BlockCondition::TagExists(name, _) => format!("Tag '{}' exists", name),
+            BlockCondition::EmptyCommit(_) => "Commit has no changes".into(),
+            BlockCondition::WorktreeDirty(_) => "Working tree has unstaged or untracked changes".into(),
             BlockCondition::EnvEquals(key, val, _) => {
                 format!("Environment {} must equal \"{}\"", key, val)
             }
+            BlockCondition::EnvNotEquals(key, val, _) => {
+                format!("Environment {} must not equal \"{}\"", key, val)
+            }
+            BlockCondition::EnvContains(key, val, _) => {
+                format!("Environment {} must contain \"{}\"", key, val)
+            }
             BlockCondition::MacroCall { name, .. } => {
                 format!("@{} check passed", name)
             }
@@ -338,6 +417,13 @@ impl BlockCondition {
     }
 }
 
+fn scoped_stat(name: &str, pattern: &Option<String>) -> String {
+    match pattern {
+        Some(p) => format!("{} in {}", name, p),
+        None => name.to_string(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MessageCheck {
     Match(String, Span),
@@ -347,11 +433,13 @@ pub enum MessageCheck {
 impl Statement {
     pub fn span(&self) -> Span {
         match self {
-            Statement::Run(_, span) => *span,
+            Statement::Run { span, .. } => *span,
+            Statement::Comment { span, .. } => *span,
             Statement::Block(_, span) => *span,
             Statement::BoolLiteral(_, span) => *span,
             Statement::Parallel { span, .. } => *span,
             Statement::LetStringList { span, .. } => *span,
+            Statement::Assign { span, .. } => *span,
             Statement::ForEachStringList { span, .. } => *span,
             Statement::ForEachArray { span, .. } => *span,
             Statement::ForEachStagedFiles { span, .. } => *span,
@@ -364,6 +452,7 @@ impl Statement {
             Statement::StagedContentValidation { span, .. } => *span,
             Statement::StagedContentForeach { span, .. } => *span,
             Statement::AllowCommand(_, span) => *span,
+            Statement::RequireClean(span) => *span,
             Statement::AllFiles { span, .. } => *span,
             Statement::FileRule { span, .. } => *span,
             Statement::ContentValidation { span, .. } => *span,
@@ -381,6 +470,7 @@ impl MatchSubject {
             MatchSubject::File(span) => *span,
             MatchSubject::Content(span) => *span,
             MatchSubject::Diff(span) => *span,
+            MatchSubject::Branch(span) => *span,
         }
     }
 }
@@ -464,16 +554,20 @@ impl PropertyValue {
             PropertyValue::BranchName(span) => *span,
             PropertyValue::Content(span) => *span,
             PropertyValue::Diff(span) => *span,
+            PropertyValue::AddedLines(span) => *span,
             PropertyValue::CommitMessage(span) => *span,
             PropertyValue::Extension(span) => *span,
             PropertyValue::Filename(span) => *span,
             PropertyValue::Basename(span) => *span,
             PropertyValue::Dirname(span) => *span,
-            PropertyValue::ModifiedLines(span) => *span,
-            PropertyValue::FilesChanged(span) => *span,
-            PropertyValue::Additions(span) => *span,
-            PropertyValue::Deletions(span) => *span,
+            PropertyValue::ModifiedLines(_, span) => *span,
+            PropertyValue::FilesChanged(_, span) => *span,
+            PropertyValue::StagedCount(span) => *span,
+            PropertyValue::Additions(_, span) => *span,
+            PropertyValue::Deletions(_, span) => *span,
             PropertyValue::CommitsAhead(span) => *span,
+            PropertyValue::SubjectLength(span) => *span,
+            PropertyValue::MaxLineLength(span) => *span,
             PropertyValue::EnvVar(_, span) => *span,
             PropertyValue::Placeholder(_, span) => *span,
         }
@@ -502,7 +596,16 @@ impl BlockCondition {
             BlockCondition::AuthorSet(span) => *span,
             BlockCondition::AuthorEmailSet(span) => *span,
             BlockCondition::AuthorMissing(span) => *span,
+            BlockCondition::IsBinary(span) => *span,
+            BlockCondition::AnyBinary(span) => *span,
+            BlockCondition::MergeInProgress(span) => *span,
+            BlockCondition::HasConflicts(span) => *span,
+            BlockCondition::TagExists(_, span) => *span,
+            BlockCondition::EmptyCommit(span) => *span,
+            BlockCondition::WorktreeDirty(span) => *span,
             BlockCondition::EnvEquals(_, _, span) => *span,
+            BlockCondition::EnvNotEquals(_, _, span) => *span,
+            BlockCondition::EnvContains(_, _, span) => *span,
             BlockCondition::MacroCall { span, .. } => *span,
             BlockCondition::NotMacroCall { span, .. } => *span,
             BlockCondition::Not { span, .. } => *span,
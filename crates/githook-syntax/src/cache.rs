@@ -44,13 +44,11 @@ impl ParseCache {
             return None;
         }
 
-        if let Some(cached_hash) = entry.hash {
-            if let Ok(content) = std::fs::read_to_string(path) {
-                let current_hash = Self::hash_content(&content);
-                if current_hash != cached_hash {
-                    return None;
-                }
-            }
+        if let Some(cached_hash) = entry.hash
+            && let Ok(content) = std::fs::read_to_string(path)
+            && Self::hash_content(&content) != cached_hash
+        {
+            return None;
         }
 
         Some(entry.statements.clone())
@@ -75,10 +73,10 @@ impl ParseCache {
         };
 
         if let Ok(mut cache) = self.cache.write() {
-            if cache.len() >= self.max_entries {
-                if let Some(oldest_key) = cache.keys().next().cloned() {
-                    cache.remove(&oldest_key);
-                }
+            if cache.len() >= self.max_entries
+                && let Some(oldest_key) = cache.keys().next().cloned()
+            {
+                cache.remove(&oldest_key);
             }
             cache.insert(path, entry);
         }
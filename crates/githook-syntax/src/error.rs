@@ -48,6 +48,18 @@ impl LexError {
             LexError::UnexpectedEof { span, .. } => *span,
         }
     }
+
+    /// Stable error code, see [`explain`]
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexError::UnexpectedChar { .. } => "E020",
+            LexError::UnterminatedString { .. } => "E021",
+            LexError::UnterminatedComment { .. } => "E022",
+            LexError::InvalidNumber { .. } => "E023",
+            LexError::InvalidEscape { .. } => "E024",
+            LexError::UnexpectedEof { .. } => "E025",
+        }
+    }
 }
 
 impl fmt::Display for LexError {
@@ -100,6 +112,17 @@ impl ParseError {
             ParseError::LexError(e) => Some(e.span()),
         }
     }
+
+    /// Stable error code, see [`explain`]
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedToken { .. } => "E010",
+            ParseError::UnexpectedEof { .. } => "E011",
+            ParseError::MissingToken { .. } => "E012",
+            ParseError::InvalidSyntax { .. } => "E013",
+            ParseError::LexError(e) => e.code(),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -134,9 +157,76 @@ impl From<LexError> for ParseError {
     }
 }
 
+/// Error code reported when no hook file can be found for the requested hook
+pub const MISSING_CONFIG_CODE: &str = "E001";
+
+/// Longer description of an error code, as printed by `githook explain`
+pub fn explain(code: &str) -> Option<&'static str> {
+    let text = match code.to_ascii_uppercase().as_str() {
+        "E001" => "\
+No hook file was found for the hook being run.
+
+githook looks for `.githook/<hook>.ghook`, `.git/hooks/<hook>.ghook` and
+`<hook>.ghook`, first in the current directory and then in each parent
+directory up to the repository root. Create one of these files, or pass the
+path of a `.ghook` file explicitly:
+
+    githook path/to/hook.ghook",
+        "E010" => "\
+The parser found a token it did not expect at this position.
+
+This usually means a keyword is misspelled, a string is missing its quotes,
+or a condition is incomplete. Compare the line with the expected form shown
+in the message, e.g.:
+
+    block_if content contains \"TODO\" message \"Remove TODOs\"",
+        "E011" => "\
+The file ended while a statement was still being parsed.
+
+Most often a block opened with `{` was never closed with `}`. Check that
+every `group`, `foreach`, `when`, `match`, `parallel` and `macro` body has a
+matching closing brace.",
+        "E012" => "\
+A required token is missing, for example the `{` that starts a block or the
+`message` keyword after a `warn_if`/`block_if` condition.",
+        "E013" => "\
+The statement is syntactically invalid. The message describes which part of
+the statement could not be understood.",
+        "E020" => "\
+The file contains a character that is not part of the language. If the
+message suggests an alternative, the character was probably mistyped.
+Text that should be kept as-is has to be written inside a string literal.",
+        "E021" => "\
+A string literal was opened with `\"` but never closed. Strings may not span
+multiple lines; use `\\n` to include a line break.",
+        "E022" => "\
+A multi-line comment was opened with `/*` but never closed with `*/`.",
+        "E023" => "\
+A number could not be read, for example because it is too large or contains
+more than one decimal point.",
+        "E024" => "\
+A string contains an invalid escape sequence. Supported escapes are `\\n`,
+`\\t`, `\\r`, `\\\"`, `\\\\` and `\\u{...}` with a hexadecimal code point.",
+        "E025" => "\
+The file ended in the middle of a token, for example right after an opening
+quote or an escape character.",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Zero-based line and character columns of a diagnostic, as editors count them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LineRange {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
 pub struct Diagnostic<'a> {
     source: &'a str,
     error: DiagnosticError,
+    color: bool,
 }
 
 pub enum DiagnosticError {
@@ -146,11 +236,17 @@ pub enum DiagnosticError {
 
 impl<'a> Diagnostic<'a> {
     pub fn new_lex(source: &'a str, error: LexError) -> Self {
-        Self { source, error: DiagnosticError::Lex(error) }
+        Self { source, error: DiagnosticError::Lex(error), color: true }
     }
 
     pub fn new_parse(source: &'a str, error: ParseError) -> Self {
-        Self { source, error: DiagnosticError::Parse(error) }
+        Self { source, error: DiagnosticError::Parse(error), color: true }
+    }
+
+    /// Render with or without ANSI colors
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
     }
 
     fn span(&self) -> Option<Span> {
@@ -160,13 +256,46 @@ impl<'a> Diagnostic<'a> {
         }
     }
 
-    fn message(&self) -> String {
+    pub fn message(&self) -> String {
         match &self.error {
             DiagnosticError::Lex(e) => e.to_string(),
             DiagnosticError::Parse(e) => e.to_string(),
         }
     }
 
+    pub fn code(&self) -> &'static str {
+        match &self.error {
+            DiagnosticError::Lex(e) => e.code(),
+            DiagnosticError::Parse(e) => e.code(),
+        }
+    }
+
+    /// The characters underlined by [`Diagnostic::format_error`]; errors without a
+    /// span (like an unexpected end of file) point at the start of the file
+    pub fn range(&self) -> LineRange {
+        let Some(span) = self.span() else {
+            return LineRange::default();
+        };
+
+        let start = span.col.saturating_sub(1);
+        LineRange {
+            line: span.line.saturating_sub(1),
+            start,
+            end: start + self.error_len(span),
+        }
+    }
+
+    /// Length of the underlined text in characters, at least one
+    fn error_len(&self, span: Span) -> usize {
+        if span.end > span.start && span.end <= self.source.len() {
+            self.source
+                .get(span.start..span.end)
+                .map_or(1, |text| text.chars().count().max(1))
+        } else {
+            1
+        }
+    }
+
     fn error_label(&self) -> &str {
         match &self.error {
             DiagnosticError::Lex(_) => "lexical error",
@@ -180,12 +309,17 @@ impl<'a> Diagnostic<'a> {
         let span = self.span();
         let message = self.message();
         let label = self.error_label();
+        let (red, blue, reset) = if self.color {
+            ("\x1b[1;31m", "\x1b[1;34m", "\x1b[0m")
+        } else {
+            ("", "", "")
+        };
 
-        output.push_str(&format!("\x1b[1;31merror\x1b[0m: {}\n", message));
+        output.push_str(&format!("{red}error[{}]{reset}: {}\n", self.code(), message));
 
         if let Some(span) = span {
-            output.push_str(&format!("  \x1b[1;34m-->\x1b[0m line {}:{}\n", span.line, span.col));
-            output.push_str("   \x1b[1;34m|\x1b[0m\n");
+            output.push_str(&format!("  {blue}-->{reset} line {}:{}\n", span.line, span.col));
+            output.push_str(&format!("   {blue}|{reset}\n"));
 
             let lines: Vec<&str> = self.source.lines().collect();
             
@@ -194,7 +328,7 @@ impl<'a> Diagnostic<'a> {
                 let line_content = lines[line_idx];
                 
                 let line_num_width = (span.line + 1).to_string().len().max(2);
-                output.push_str(&format!(" {: >width$} \x1b[1;34m|\x1b[0m {}\n", 
+                output.push_str(&format!(" {: >width$} {blue}|{reset} {}\n", 
                     span.line, line_content, width = line_num_width));
 
                 let mut visual_col = 0;
@@ -205,18 +339,9 @@ impl<'a> Diagnostic<'a> {
                     visual_col += if ch == '\t' { 4 } else { 1 };
                 }
 
-                let error_len = if span.end > span.start {
-                    let error_text = if span.start < self.source.len() && span.end <= self.source.len() {
-                        &self.source[span.start..span.end]
-                    } else {
-                        "?"
-                    };
-                    error_text.chars().count().max(1)
-                } else {
-                    1
-                };
+                let error_len = self.error_len(span);
 
-                output.push_str(&format!(" {: >width$} \x1b[1;34m|\x1b[0m {}\x1b[1;31m{}\x1b[0m {}\n",
+                output.push_str(&format!(" {: >width$} {blue}|{reset} {}{red}{}{reset} {}\n",
                     "",
                     " ".repeat(visual_col),
                     "^".repeat(error_len),
@@ -225,11 +350,13 @@ impl<'a> Diagnostic<'a> {
                 ));
             }
 
-            output.push_str("   \x1b[1;34m|\x1b[0m\n");
+            output.push_str(&format!("   {blue}|{reset}\n"));
         } else {
-            output.push_str(&format!("   \x1b[1;34m|\x1b[0m {}\n", label));
+            output.push_str(&format!("   {blue}|{reset} {}\n", label));
         }
 
+        output.push_str(&format!("   {blue}={reset} run `githook explain {}` for more information\n", self.code()));
+
         output
     }
 }
@@ -239,3 +366,53 @@ impl<'a> fmt::Display for Diagnostic<'a> {
         write!(f, "{}", self.format_error())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_spanned, tokenize_with_spans};
+
+    #[test]
+    fn parse_error_reports_its_code() {
+        let source = "block (\n";
+        let error = parse_spanned(tokenize_with_spans(source).unwrap()).unwrap_err();
+        assert_eq!(error.code(), "E013");
+
+        let rendered = Diagnostic::new_parse(source, error).with_color(false).format_error();
+        assert!(rendered.starts_with("error[E013]: "));
+        assert!(rendered.contains("run `githook explain E013`"));
+    }
+
+    #[test]
+    fn lex_error_reports_its_code() {
+        let error = tokenize_with_spans("run \"a\" /* never closed").unwrap_err();
+        assert_eq!(error.code(), "E022");
+    }
+
+    #[test]
+    fn explain_describes_known_codes() {
+        assert!(explain("E011").unwrap().contains("`when`, `match`, `parallel`"));
+        assert!(explain("e022").unwrap().contains("`*/`"));
+        assert_eq!(explain(MISSING_CONFIG_CODE), explain("E001"));
+        assert!(explain("E999").is_none());
+    }
+
+    #[test]
+    fn range_is_zero_based_and_covers_the_token() {
+        let source = "run \"a\"\nwhen  ünïcode {\n";
+        let error = ParseError::UnexpectedToken {
+            expected: "condition".to_string(),
+            found: "ünïcode".to_string(),
+            span: Span::new(2, 7, 14, 23),
+        };
+
+        let range = Diagnostic::new_parse(source, error).range();
+        assert_eq!(range, LineRange { line: 1, start: 6, end: 13 });
+    }
+
+    #[test]
+    fn range_without_span_points_at_the_start() {
+        let error = ParseError::UnexpectedEof { expected: "}".to_string(), context: None };
+        assert_eq!(Diagnostic::new_parse("group g {\n", error).range(), LineRange::default());
+    }
+}
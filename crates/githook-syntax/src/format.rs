@@ -0,0 +1,530 @@
+use crate::ast::*;
+
+const INDENT: &str = "  ";
+
+/// Render a parsed script back into canonical `.ghook` source.
+///
+/// Blocks use two-space indentation with the opening brace on the same line,
+/// and top-level blocks are separated by a blank line. Formatting the output
+/// again yields the same text. Comments kept as statements stay attached to the
+/// statement they precede or trail; comments inside conditions are dropped.
+pub fn format(statements: &[Statement]) -> String {
+    let mut out = String::new();
+    let mut prev_multiline = false;
+
+    for (i, stmt) in statements.iter().enumerate() {
+        if let Statement::Comment { text, trailing: true, .. } = stmt
+            && out.ends_with('\n')
+        {
+            out.pop();
+            out.push_str(&format!(" {}\n", text));
+            continue;
+        }
+
+        let rendered = format_statement(stmt, 0);
+        // Leading comments are laid out like the statement they belong to
+        let multiline = if matches!(stmt, Statement::Comment { .. }) {
+            statements[i + 1..]
+                .iter()
+                .find(|s| !matches!(s, Statement::Comment { .. }))
+                .is_some_and(|s| format_statement(s, 0).contains('\n'))
+        } else {
+            rendered.contains('\n')
+        };
+        let after_comment = i > 0 && matches!(statements[i - 1], Statement::Comment { trailing: false, .. });
+
+        if i > 0 && !after_comment && (multiline || prev_multiline) {
+            out.push('\n');
+        }
+        out.push_str(&rendered);
+        out.push('\n');
+
+        prev_multiline = multiline;
+    }
+
+    out
+}
+
+fn format_statement(stmt: &Statement, depth: usize) -> String {
+    let pad = INDENT.repeat(depth);
+
+    match stmt {
+        Statement::Run { command, cwd, env, fixes, retries, backoff, .. } => {
+            let mut line = format!("{}run {}", pad, quote(command));
+            if let Some(dir) = cwd {
+                line.push_str(&format!(" in {}", quote(dir)));
+            }
+            if !env.is_empty() {
+                let vars: Vec<String> = env
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", quote(key), quote(value)))
+                    .collect();
+                line.push_str(&format!(" env {{ {} }}", vars.join(", ")));
+            }
+            if *fixes {
+                line.push_str(" fixes");
+            }
+            if *retries > 0 {
+                line.push_str(&format!(" retry {}", retries));
+                if *backoff > 0.0 {
+                    line.push_str(&format!(" backoff {}", backoff));
+                }
+            }
+            line
+        }
+        Statement::Comment { text, .. } => format!("{}{}", pad, text),
+        Statement::Block(message, _) => format!("{}block {}", pad, quote(message)),
+        Statement::BoolLiteral(value, _) => format!("{}{}", pad, value),
+        Statement::AllowCommand(command, _) => format!("{}allow {}", pad, quote(command)),
+        Statement::RequireClean(_) => format!("{}require clean", pad),
+        Statement::Parallel { commands, pattern, .. } => {
+            let mut header = "parallel".to_string();
+            if let Some(p) = pattern {
+                header.push_str(&format!(" matching {}", quote(p)));
+            }
+            let lines: Vec<String> = commands
+                .iter()
+                .map(|cmd| format!("{}{}run {}", pad, INDENT, quote(cmd)))
+                .collect();
+            wrap_block(&pad, &header, lines)
+        }
+        Statement::LetStringList { name, items, .. } => {
+            let items: Vec<String> = items.iter().map(|s| quote(s)).collect();
+            format!("{}let {} = [{}]", pad, name, items.join(", "))
+        }
+        Statement::Assign { name, items, .. } => {
+            let items: Vec<String> = items.iter().map(|s| quote(s)).collect();
+            format!("{}{} = [{}]", pad, name, items.join(", "))
+        }
+        Statement::ForEachStringList { var, list, body, .. } => {
+            let header = format!("foreach {} in {{{}}}", var, list);
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::ForEachArray { var, items, body, .. } => {
+            let header = format!("foreach {} in [{}]", var, format_arguments(items));
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::ForEachStagedFiles { var, source, pattern, where_cond, body, .. } => {
+            let mut header = format!("foreach {} in {}", var, source);
+            if pattern != "*" {
+                header.push_str(&format!(" matching {}", quote(pattern)));
+            }
+            if let Some(cond) = where_cond {
+                header.push_str(&format!(" where {}", format_condition(cond)));
+            }
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::MacroDefinition { name, params, body, .. } => {
+            let header = format!("macro {}({})", name, params.join(", "));
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::MacroCall { namespace, name, args, .. } => match namespace {
+            Some(ns) => format!("{}@{}:{}({})", pad, ns, name, format_arguments(args)),
+            None => format!("{}@{}({})", pad, name, format_arguments(args)),
+        },
+        Statement::Use { namespace, name, alias, .. } => {
+            let spec = quote(&format!("@{}/{}", namespace, name));
+            match alias {
+                Some(a) => format!("{}use {} as {}", pad, spec, a),
+                None => format!("{}use {}", pad, spec),
+            }
+        }
+        Statement::Import { path, alias, .. } => match alias {
+            Some(a) => format!("{}import {} as {}", pad, quote(path), a),
+            None => format!("{}import {}", pad, quote(path)),
+        },
+        Statement::Group { definition, .. } => {
+            let inner = format!("{}{}", pad, INDENT);
+            let mut lines = Vec::new();
+            if let Some(severity) = &definition.severity {
+                let level = match severity {
+                    GroupSeverity::Critical(_) => "critical",
+                    GroupSeverity::Warning(_) => "warning",
+                    GroupSeverity::Info(_) => "info",
+                };
+                lines.push(format!("{}severity: {}", inner, level));
+            }
+            if let Some(enabled) = definition.enabled {
+                lines.push(format!("{}enabled: {}", inner, enabled));
+            }
+            lines.extend(format_body(&definition.body, depth + 1));
+            wrap_block(&pad, &format!("group {}", definition.name), lines)
+        }
+        Statement::StagedFiles { pattern, body, .. } => {
+            let header = format!("staged_files {}", quote(pattern));
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::AllFiles { pattern, body, .. } => {
+            let header = format!("all_files {}", quote(pattern));
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::StagedContentForeach { pattern, body, .. } => {
+            let header = format!("staged_content {}", quote(pattern));
+            wrap_block(&pad, &header, format_body(body, depth + 1))
+        }
+        Statement::StagedContentValidation { must, check, pattern, .. } => {
+            let mut line = format!("{}staged_content", pad);
+            if let Some(p) = pattern {
+                line.push_str(&format!(" matching {}", quote(p)));
+            }
+            line.push_str(&format!(" {} {}", must_keyword(*must), format_content_check(check)));
+            line
+        }
+        Statement::ContentValidation { scope, must, check, pattern, .. } => {
+            let scope = match scope {
+                ContentScope::Content(_) => "content",
+                ContentScope::Diff(_) => "diff",
+            };
+            let mut line = format!("{}{} {} {}", pad, scope, must_keyword(*must), format_content_check(check));
+            if let Some(p) = pattern {
+                line.push_str(&format!(" matching {}", quote(p)));
+            }
+            line
+        }
+        Statement::MessageValidation { must, check, .. } => {
+            let check = match check {
+                MessageCheck::Match(s, _) => format!("match {}", quote(s)),
+                MessageCheck::Contain(s, _) => format!("contain {}", quote(s)),
+            };
+            format!("{}commit_message {} {}", pad, must_keyword(*must), check)
+        }
+        Statement::FileRule { pattern, must_be_staged, .. } => {
+            if *must_be_staged {
+                format!("{}{} must be staged", pad, quote(pattern))
+            } else {
+                format!("{}{} must not be staged", pad, quote(pattern))
+            }
+        }
+        Statement::ConditionalRule { severity, condition, message, interactive, .. } => {
+            let keyword = match severity {
+                RuleSeverity::Block(_) => "block_if",
+                RuleSeverity::Warn(_) => "warn_if",
+            };
+            let mut line = format!("{}{} {}", pad, keyword, format_condition(condition));
+            if let Some(m) = message {
+                line.push_str(&format!(" message {}", quote(m)));
+            }
+            if let Some(i) = interactive {
+                line.push_str(&format!(" interactive {}", quote(i)));
+            }
+            line
+        }
+        Statement::When { condition, body, else_body, .. } => {
+            let header = format!("when {}", format_condition(condition));
+            let mut out = wrap_block(&pad, &header, format_body(body, depth + 1));
+            if let Some(else_stmts) = else_body {
+                // Reopen the closing brace line as "} else {"
+                out.push_str(" else {\n");
+                for line in format_body(else_stmts, depth + 1) {
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                out.push_str(&pad);
+                out.push('}');
+            }
+            out
+        }
+        Statement::Match { subject, arms, .. } => {
+            let subject = match subject {
+                MatchSubject::File(_) => "file",
+                MatchSubject::Content(_) => "content",
+                MatchSubject::Diff(_) => "diff",
+                MatchSubject::Branch(_) => "branch",
+            };
+            let inner = format!("{}{}", pad, INDENT);
+            let mut lines = Vec::new();
+            for arm in arms {
+                let pattern = match &arm.pattern {
+                    MatchPattern::Wildcard(s, _) => quote(s),
+                    MatchPattern::Contains(s, _) => format!("contains {}", quote(s)),
+                    MatchPattern::Matches(s, _) => format!("matches {}", quote(s)),
+                    MatchPattern::GreaterThan(n, _) => format!("> {}", n),
+                    MatchPattern::LessThan(n, _) => format!("< {}", n),
+                };
+                for action in &arm.action {
+                    let rendered = format_statement(action, depth + 1);
+                    let action = rendered.strip_prefix(inner.as_str()).unwrap_or(&rendered);
+                    lines.push(format!("{}{} -> {}", inner, pattern, action));
+                }
+            }
+            wrap_block(&pad, &format!("match {}", subject), lines)
+        }
+    }
+}
+
+fn format_body(body: &[Statement], depth: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::with_capacity(body.len());
+    for stmt in body {
+        match (stmt, lines.last_mut()) {
+            (Statement::Comment { text, trailing: true, .. }, Some(last)) => {
+                last.push(' ');
+                last.push_str(text);
+            }
+            _ => lines.push(format_statement(stmt, depth)),
+        }
+    }
+    lines
+}
+
+/// Emit `header {`, the already indented body lines and a closing brace
+fn wrap_block(pad: &str, header: &str, lines: Vec<String>) -> String {
+    let mut out = format!("{}{} {{\n", pad, header);
+    for line in lines {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str(pad);
+    out.push('}');
+    out
+}
+
+fn format_condition(cond: &BlockCondition) -> String {
+    match cond {
+        BlockCondition::Comparison { left, operator, right, negated, .. } => {
+            let op = match operator {
+                ComparisonOperator::Greater => ">",
+                ComparisonOperator::GreaterOrEqual => ">=",
+                ComparisonOperator::Less => "<",
+                ComparisonOperator::LessOrEqual => "<=",
+                ComparisonOperator::Equals => "==",
+                ComparisonOperator::Matches => "matches",
+                ComparisonOperator::Contains => "contains",
+                ComparisonOperator::In => "in",
+            };
+            let text = format!("{} {} {}", format_property(left), op, format_comparison_value(right));
+            if *negated {
+                format!("not {}", text)
+            } else {
+                text
+            }
+        }
+        BlockCondition::InStringList { value, list, .. } => format!("{} in {{{}}}", quote(value), list),
+        BlockCondition::StringEquals { left, right, right_is_identifier, .. } => {
+            if *right_is_identifier {
+                format!("{} == {}", quote(left), right)
+            } else {
+                format!("{} == {}", quote(left), quote(right))
+            }
+        }
+        BlockCondition::ContentCheck { scope, check, .. } => {
+            let scope = match scope {
+                ContentScope::Content(_) => "content",
+                ContentScope::Diff(_) => "diff",
+            };
+            format!("{} {}", scope, format_content_check(check))
+        }
+        BlockCondition::ContainsSecrets(_) => "contains_secrets".to_string(),
+        BlockCondition::AuthorSet(_) => "author_set".to_string(),
+        BlockCondition::AuthorEmailSet(_) => "author_email_set".to_string(),
+        BlockCondition::AuthorMissing(_) => "author_missing".to_string(),
+        BlockCondition::IsBinary(_) => "is_binary".to_string(),
+        BlockCondition::AnyBinary(_) => "any_binary".to_string(),
+        BlockCondition::MergeInProgress(_) => "merge_in_progress".to_string(),
+        BlockCondition::HasConflicts(_) => "has_conflicts".to_string(),
+        BlockCondition::TagExists(name, _) => format!("tag_exists {}", quote(name)),
+        BlockCondition::EmptyCommit(_) => "empty_commit".to_string(),
+        BlockCondition::WorktreeDirty(_) => "worktree_dirty".to_string(),
+        BlockCondition::EnvEquals(key, value, _) => format!("env {} = {}", quote(key), quote(value)),
+        BlockCondition::EnvNotEquals(key, value, _) => format!("env {} != {}", quote(key), quote(value)),
+        BlockCondition::EnvContains(key, value, _) => format!("env {} contains {}", quote(key), quote(value)),
+        BlockCondition::MacroCall { name, args, .. } => format!("@{}({})", name, format_arguments(args)),
+        BlockCondition::NotMacroCall { name, args, .. } => format!("not @{}({})", name, format_arguments(args)),
+        BlockCondition::Not { inner, .. } => match inner.as_ref() {
+            // `not` cannot be stacked without parentheses
+            BlockCondition::Not { .. }
+            | BlockCondition::NotMacroCall { .. }
+            | BlockCondition::Comparison { negated: true, .. } => format!("not ({})", format_condition(inner)),
+            _ => format!("not {}", format_operand(inner, 2)),
+        },
+        BlockCondition::And { left, right, .. } => {
+            format!("{} and {}", format_operand(left, 1), format_operand(right, 2))
+        }
+        BlockCondition::Or { left, right, .. } => {
+            format!("{} or {}", format_operand(left, 0), format_operand(right, 1))
+        }
+        BlockCondition::Bool(value, _) => value.to_string(),
+    }
+}
+
+/// Format a sub-condition, parenthesizing it if it binds looser than `min_precedence`
+/// (0 = or, 1 = and, 2 = atom)
+fn format_operand(cond: &BlockCondition, min_precedence: u8) -> String {
+    let precedence = match cond {
+        BlockCondition::Or { .. } => 0,
+        BlockCondition::And { .. } => 1,
+        _ => 2,
+    };
+
+    if precedence < min_precedence {
+        format!("({})", format_condition(cond))
+    } else {
+        format_condition(cond)
+    }
+}
+
+fn format_property(property: &PropertyValue) -> String {
+    match property {
+        PropertyValue::FileSize(_) => "file_size".to_string(),
+        PropertyValue::BranchName(_) => "branch_name".to_string(),
+        PropertyValue::Content(_) => "content".to_string(),
+        PropertyValue::Diff(_) => "diff".to_string(),
+        PropertyValue::AddedLines(_) => "added_lines".to_string(),
+        PropertyValue::CommitMessage(_) => "commit_message".to_string(),
+        PropertyValue::Extension(_) => "extension".to_string(),
+        PropertyValue::Filename(_) => "filename".to_string(),
+        PropertyValue::Basename(_) => "basename".to_string(),
+        PropertyValue::Dirname(_) => "dirname".to_string(),
+        PropertyValue::ModifiedLines(pattern, _) => format_scoped_stat("modified_lines", pattern),
+        PropertyValue::FilesChanged(pattern, _) => format_scoped_stat("files_changed", pattern),
+        PropertyValue::StagedCount(_) => "staged_count".to_string(),
+        PropertyValue::Additions(pattern, _) => format_scoped_stat("additions", pattern),
+        PropertyValue::Deletions(pattern, _) => format_scoped_stat("deletions", pattern),
+        PropertyValue::CommitsAhead(_) => "commits_ahead".to_string(),
+        PropertyValue::SubjectLength(_) => "subject_length".to_string(),
+        PropertyValue::MaxLineLength(_) => "max_line_length".to_string(),
+        PropertyValue::EnvVar(key, _) => format!("env {}", quote(key)),
+        PropertyValue::Placeholder(name, _) => name.clone(),
+    }
+}
+
+fn format_comparison_value(value: &ComparisonValue) -> String {
+    match value {
+        ComparisonValue::String(s, _) => quote(s),
+        ComparisonValue::Number(n, _) => n.to_string(),
+        ComparisonValue::Identifier(id, _) => id.clone(),
+        ComparisonValue::ListIdentifier(id, _) => format!("{{{}}}", id),
+    }
+}
+
+fn format_content_check(check: &ContentCheck) -> String {
+    match check {
+        ContentCheck::Match(s, _) => format!("match {}", quote(s)),
+        ContentCheck::Contain(s, _) => format!("contain {}", quote(s)),
+    }
+}
+
+fn format_arguments(args: &[Argument]) -> String {
+    args.iter().map(format_argument).collect::<Vec<_>>().join(", ")
+}
+
+fn format_argument(arg: &Argument) -> String {
+    match arg {
+        Argument::String(s, _) => quote(s),
+        Argument::Number(n, _) => n.to_string(),
+        Argument::Identifier(id, _) => id.clone(),
+        Argument::Array(items, _) => format!("[{}]", format_arguments(items)),
+    }
+}
+
+fn format_scoped_stat(name: &str, pattern: &Option<String>) -> String {
+    match pattern {
+        Some(p) => format!("{} matching {}", name, quote(p)),
+        None => name.to_string(),
+    }
+}
+
+fn must_keyword(must: bool) -> &'static str {
+    if must { "must" } else { "must not" }
+}
+
+/// Quote a string literal, escaping what the lexer would otherwise decode
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            // A lone backslash stays as written unless it would start an escape
+            '\\' => match chars.peek() {
+                None | Some('n' | 't' | 'r' | '"' | '\\' | 'u') => out.push_str("\\\\"),
+                Some(_) => out.push('\\'),
+            },
+            _ => out.push(ch),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_spanned, tokenize_with_spans};
+
+    fn format_source(source: &str) -> String {
+        format(&parse_spanned(tokenize_with_spans(source).unwrap()).unwrap())
+    }
+
+    const MESSY: &str = r#"# Lint before committing
+let   exts = ["rs",   "toml"]
+group  lint { severity: warning
+enabled: true
+run "cargo clippy"   # trailing
+}
+foreach ext in staged_files matching "*.rs" { run "echo {ext}" }
+when branch_name == "main" { block_if true message "no" } else { warn_if true message "careful" }
+match file { "*.rs" -> run "rustfmt"
+  contains "TODO" -> warn_if true message "todo" }
+parallel matching "*.rs" { run "a"
+run "b" }
+macro check { @lint_all }
+/* block
+comment */
+run "done"
+"#;
+
+    const CANONICAL: &str = r#"# Lint before committing
+let exts = ["rs", "toml"]
+
+group lint {
+  severity: warning
+  enabled: true
+  run "cargo clippy" # trailing
+}
+
+foreach ext in staged_files matching "*.rs" {
+  run "echo {ext}"
+}
+
+when branch_name == "main" {
+  block_if true message "no"
+} else {
+  warn_if true message "careful"
+}
+
+match file {
+  "*.rs" -> run "rustfmt"
+  contains "TODO" -> warn_if true message "todo"
+}
+
+parallel matching "*.rs" {
+  run "a"
+  run "b"
+}
+
+macro check() {
+  @lint_all()
+}
+
+/* block
+comment */
+run "done"
+"#;
+
+    #[test]
+    fn formats_into_canonical_layout() {
+        assert_eq!(format_source(MESSY), CANONICAL);
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let once = format_source(MESSY);
+        assert_eq!(format_source(&once), once);
+    }
+}
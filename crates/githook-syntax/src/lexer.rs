@@ -14,18 +14,34 @@ pub enum Token {
     Parallel,
     Let,
     StagedFiles,
+    AddedFiles,
+    DeletedFiles,
+    ChangedSince,
+    CommitFiles,
+    ConflictFiles,
     AllFiles,
     BranchName,
     CommitMessage,
     AuthorEmail,
     AuthorSet,
+    IsBinary,
+    AnyBinary,
+    MergeInProgress,
+    HasConflicts,
+    TagExists,
+    EmptyCommit,
+    WorktreeDirty,
     AuthorEmailSet,
     AuthorMissing,
     ModifiedLines,
     FilesChanged,
+    StagedCount,
     Additions,
+    AddedLines,
     Deletions,
     CommitsAhead,
+    SubjectLength,
+    MaxLineLength,
     FileExists,
     FileSize,
     Content,
@@ -34,6 +50,7 @@ pub enum Token {
     BeStaged,
     Foreach,
     Matches,
+    IgnoreCase,
     Matching,
     Must,
     Match,
@@ -263,14 +280,12 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
                         bump(ch, &mut line, &mut col, &mut offset);
                         comment.push(ch);
                         
-                        if ch == '*' {
-                            if let Some(&'/') = chars.peek() {
-                                chars.next();
-                                bump('/', &mut line, &mut col, &mut offset);
-                                comment.push('/');
-                                found_end = true;
-                                break;
-                            }
+                        if ch == '*' && let Some(&'/') = chars.peek() {
+                            chars.next();
+                            bump('/', &mut line, &mut col, &mut offset);
+                            comment.push('/');
+                            found_end = true;
+                            break;
                         }
                     }
                     
@@ -362,6 +377,62 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
                         bump(ch, &mut line, &mut col, &mut offset);
                         break;
                     }
+                    if ch == '\\' {
+                        let (esc_line, esc_col, esc_offset) = (line, col, offset);
+                        chars.next();
+                        bump(ch, &mut line, &mut col, &mut offset);
+
+                        let Some(&esc) = chars.peek() else {
+                            string.push('\\');
+                            break;
+                        };
+                        chars.next();
+                        bump(esc, &mut line, &mut col, &mut offset);
+
+                        match esc {
+                            'n' => string.push('\n'),
+                            't' => string.push('\t'),
+                            'r' => string.push('\r'),
+                            '"' => string.push('"'),
+                            '\\' => string.push('\\'),
+                            'u' => {
+                                let mut hex = String::new();
+                                let mut closed = false;
+                                if chars.peek() == Some(&'{') {
+                                    chars.next();
+                                    bump('{', &mut line, &mut col, &mut offset);
+                                    while let Some(&h) = chars.peek() {
+                                        if h == '}' {
+                                            chars.next();
+                                            bump(h, &mut line, &mut col, &mut offset);
+                                            closed = true;
+                                            break;
+                                        }
+                                        if !h.is_ascii_hexdigit() || hex.len() >= 6 {
+                                            break;
+                                        }
+                                        hex.push(h);
+                                        chars.next();
+                                        bump(h, &mut line, &mut col, &mut offset);
+                                    }
+                                }
+                                let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                                match decoded {
+                                    Some(c) if closed => string.push(c),
+                                    _ => return Err(LexError::InvalidEscape {
+                                        ch: 'u',
+                                        span: Span::new(esc_line, esc_col, esc_offset, offset),
+                                    }),
+                                }
+                            }
+                            // Unknown escapes are kept as written so regexes like "\d+" still work
+                            other => {
+                                string.push('\\');
+                                string.push(other);
+                            }
+                        }
+                        continue;
+                    }
                     string.push(ch);
                     chars.next();
                     bump(ch, &mut line, &mut col, &mut offset);
@@ -375,7 +446,8 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
                 let start_line = line;
                 let start_col = col;
                 let start_offset = offset;
-                let mut comment = String::from("#");
+                // The `#` itself is still pending and is collected by the loop below
+                let mut comment = String::new();
                 
                 while let Some(&ch) = chars.peek() {
                     if ch == '\n' {
@@ -438,18 +510,34 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
                     "parallel" => Token::Parallel,
                     "let" => Token::Let,
                     "staged_files" => Token::StagedFiles,
+                    "added_files" => Token::AddedFiles,
+                    "deleted_files" => Token::DeletedFiles,
+                    "changed_since" => Token::ChangedSince,
+                    "commit_files" => Token::CommitFiles,
+                    "conflict_files" => Token::ConflictFiles,
                     "all_files" => Token::AllFiles,
                     "branch_name" => Token::BranchName,
                     "commit_message" => Token::CommitMessage,
                     "author_email" => Token::AuthorEmail,
                     "author_set" => Token::AuthorSet,
+                    "is_binary" => Token::IsBinary,
+                    "any_binary" => Token::AnyBinary,
+                    "merge_in_progress" => Token::MergeInProgress,
+                    "has_conflicts" => Token::HasConflicts,
+                    "tag_exists" => Token::TagExists,
+                    "empty_commit" => Token::EmptyCommit,
+                    "worktree_dirty" => Token::WorktreeDirty,
                     "author_email_set" => Token::AuthorEmailSet,
                     "author_missing" => Token::AuthorMissing,
                     "modified_lines" => Token::ModifiedLines,
                     "files_changed" => Token::FilesChanged,
+                    "staged_count" => Token::StagedCount,
                     "additions" => Token::Additions,
+                    "added_lines" => Token::AddedLines,
                     "deletions" => Token::Deletions,
                     "commits_ahead" => Token::CommitsAhead,
+                    "subject_length" => Token::SubjectLength,
+                    "max_line_length" => Token::MaxLineLength,
                     "file_exists" => Token::FileExists,
                     "file_size" => Token::FileSize,
                     "content" => Token::Content,
@@ -461,6 +549,7 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
                     "false" => Token::False,
                     "where" => Token::Where,
                     "matches" => Token::Matches,
+                    "ignorecase" => Token::IgnoreCase,
                     "matching" => Token::Matching,
                     "must" => Token::Must,
                     "match" => Token::Match,
@@ -497,4 +586,44 @@ pub fn tokenize_with_spans(input: &str) -> Result<Vec<SpannedToken>, LexError> {
     }
 
     Ok(tokens)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_literal(source: &str) -> String {
+        match tokenize_with_spans(source).unwrap().remove(0).token {
+            Token::String(s) => s,
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_each_escape() {
+        assert_eq!(string_literal(r#""a\nb""#), "a\nb");
+        assert_eq!(string_literal(r#""a\tb""#), "a\tb");
+        assert_eq!(string_literal(r#""a\rb""#), "a\rb");
+        assert_eq!(string_literal(r#""say \"hi\"""#), "say \"hi\"");
+        assert_eq!(string_literal(r#""C:\\dir""#), "C:\\dir");
+        assert_eq!(string_literal(r#""\u{e9}\u{1F600}""#), "\u{e9}\u{1F600}");
+    }
+
+    #[test]
+    fn keeps_unknown_escapes_as_written() {
+        assert_eq!(string_literal(r#""\d+\.rs""#), "\\d+\\.rs");
+    }
+
+    #[test]
+    fn rejects_malformed_unicode_escapes() {
+        for source in [r#""\u{}""#, r#""\u{zz}""#, r#""\u{110000}""#, r#""\u41""#, r#""\u{41""#] {
+            let error = tokenize_with_spans(source).unwrap_err();
+            assert!(
+                matches!(error, LexError::InvalidEscape { ch: 'u', .. }),
+                "{} gave {:?}",
+                source,
+                error
+            );
+            assert_eq!(error.code(), "E024");
+        }
+    }
+}
@@ -1,11 +1,13 @@
 mod lexer;
 mod parser;
 mod ast;
+mod format;
 pub mod error;
 pub mod cache;
 
 pub use lexer::{Token, SpannedToken, tokenize_with_spans};
 pub use parser::parse_spanned;
 pub use ast::*;
-pub use error::{Span, LexError, ParseError, Diagnostic};
+pub use format::format;
+pub use error::{Span, LexError, ParseError, Diagnostic, LineRange, explain};
 pub use cache::{ParseCache, CacheStats};
\ No newline at end of file
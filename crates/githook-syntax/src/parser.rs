@@ -2,17 +2,63 @@ use crate::lexer::{Token, SpannedToken};
 use crate::ast::*;
 use crate::error::{ParseError, Span};
 use anyhow::{Result, bail};
+use std::rc::Rc;
+
+/// Upper bound for `run ... retry N`
+const MAX_RETRIES: usize = 10;
+/// Upper bound in seconds for `run ... backoff S`
+const MAX_BACKOFF_SECS: f64 = 300.0;
+
+/// Tokens being parsed. Cloning copies only the position, so backtracking and
+/// lookahead don't copy the remaining tokens.
+#[derive(Clone)]
+struct TokenStream {
+    tokens: Rc<Vec<SpannedToken>>,
+    pos: usize,
+}
+
+impl TokenStream {
+    fn new(tokens: Vec<SpannedToken>) -> Self {
+        Self { tokens: Rc::new(tokens), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&SpannedToken> {
+        self.tokens.get(self.pos)
+    }
+
+    /// The token `n` places after the next one, without consuming anything
+    fn peek_nth(&self, n: usize) -> Option<&SpannedToken> {
+        self.tokens.get(self.pos + n)
+    }
+}
+
+impl Iterator for TokenStream {
+    type Item = SpannedToken;
+
+    fn next(&mut self) -> Option<SpannedToken> {
+        let token = self.tokens.get(self.pos)?.clone();
+        self.pos += 1;
+        Some(token)
+    }
 
-fn peek_token(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Option<&Token> {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.tokens.len().saturating_sub(self.pos);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TokenStream {}
+
+fn peek_token(iter: &mut TokenStream) -> Option<&Token> {
     iter.peek().map(|st| &st.token)
 }
 
-fn next_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Option<(Token, Span)> {
+fn next_spanned(iter: &mut TokenStream) -> Option<(Token, Span)> {
     iter.next().map(|st| (st.token, st.span))
 }
 
 fn next_or_eof(
-    iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>,
+    iter: &mut TokenStream,
     context: &str,
 ) -> Result<(Token, Span), ParseError> {
     next_spanned(iter).ok_or_else(|| ParseError::UnexpectedEof {
@@ -21,13 +67,79 @@ fn next_or_eof(
     })
 }
 
-fn skip_newlines_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) {
+fn skip_newlines_spanned(iter: &mut TokenStream) {
     while matches!(peek_token(iter), Some(Token::Newline) | Some(Token::Comment(_))) {
         iter.next();
     }
 }
 
-fn expect_token_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>, expected: Token) -> Result<Span, ParseError> {
+/// Skip newlines and comments only when `expected` follows them, so that a comment
+/// before the next statement is not swallowed while looking for a continuation
+fn skip_newlines_before_spanned(iter: &mut TokenStream, expected: &Token) -> bool {
+    let mut n = 0;
+    while matches!(iter.peek_nth(n).map(|st| &st.token), Some(Token::Newline) | Some(Token::Comment(_))) {
+        n += 1;
+    }
+    if iter.peek_nth(n).map(|st| &st.token) != Some(expected) {
+        return false;
+    }
+    iter.pos += n;
+    true
+}
+
+/// Like `skip_newlines_spanned`, but stops at comments so they become statements.
+/// Returns whether a line break was skipped.
+fn skip_blank_lines_spanned(iter: &mut TokenStream) -> bool {
+    let mut skipped = false;
+    while matches!(peek_token(iter), Some(Token::Newline)) {
+        iter.next();
+        skipped = true;
+    }
+    skipped
+}
+
+/// Add a parsed statement to a body, marking a comment that shares the line of the previous statement as trailing
+fn push_statement(statements: &mut Vec<Statement>, stmt: Statement, after_newline: bool) {
+    let stmt = match stmt {
+        Statement::Comment { text, span, .. } if !after_newline && !statements.is_empty() => {
+            Statement::Comment { text, trailing: true, span }
+        }
+        stmt => stmt,
+    };
+    statements.push(stmt);
+}
+
+/// `env { "KEY": "value", ... }` after a `run` command, returning the closing brace span
+fn parse_env_map(iter: &mut TokenStream) -> Result<(Vec<(String, String)>, Span)> {
+    next_spanned(iter);
+    expect_token_spanned(iter, Token::LeftBrace)?;
+
+    let mut env = Vec::new();
+    loop {
+        skip_newlines_spanned(iter);
+        match next_spanned(iter) {
+            Some((Token::RightBrace, span)) => return Ok((env, span)),
+            Some((Token::String(key), _)) => {
+                expect_token_spanned(iter, Token::Colon)?;
+                let value = match next_spanned(iter) {
+                    Some((Token::String(value), _)) => value,
+                    Some((tok, span)) => bail!("Expected string value for env variable '{}', got {:?} at {:?}", key, tok, span),
+                    None => bail!("Expected string value for env variable '{}'", key),
+                };
+                env.push((key, value));
+
+                skip_newlines_spanned(iter);
+                if matches!(peek_token(iter), Some(Token::Comma)) {
+                    next_spanned(iter);
+                }
+            }
+            Some((tok, span)) => bail!("Expected env variable name or '}}', got {:?} at {:?}", tok, span),
+            None => bail!("Expected '}}' to close env block"),
+        }
+    }
+}
+
+fn expect_token_spanned(iter: &mut TokenStream, expected: Token) -> Result<Span, ParseError> {
     match next_spanned(iter) {
         Some((token, span)) if token == expected => Ok(span),
         Some((token, span)) => Err(ParseError::UnexpectedToken {
@@ -43,17 +155,17 @@ fn expect_token_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanne
 }
 
 pub fn parse_spanned(tokens: Vec<SpannedToken>) -> Result<Vec<Statement>, ParseError> {
-    let mut iter = tokens.into_iter().peekable();
+    let mut iter = TokenStream::new(tokens);
     let capacity = iter.len() / 10;
     let mut statements = Vec::with_capacity(capacity.max(8));
 
     while iter.peek().is_some() {
-        skip_newlines_spanned(&mut iter);
+        let after_newline = skip_blank_lines_spanned(&mut iter);
         if iter.peek().is_none() {
             break;
         }
         match parse_statement_spanned(&mut iter) {
-            Ok(stmt) => statements.push(stmt),
+            Ok(stmt) => push_statement(&mut statements, stmt, after_newline),
             Err(e) => {
                 let error_span = iter.peek()
                     .map(|st| st.span)
@@ -69,10 +181,18 @@ pub fn parse_spanned(tokens: Vec<SpannedToken>) -> Result<Vec<Statement>, ParseE
     Ok(statements)
 }
 
-fn parse_statement_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_statement_spanned(iter: &mut TokenStream) -> Result<Statement> {
+    // `name = [...]` reassigns an existing list
+    let is_assign = matches!(iter.peek_nth(1).map(|st| &st.token), Some(Token::Equals));
     let token_peek = peek_token(iter);
     
     match token_peek {
+        Some(Token::Comment(_)) => {
+            let (Token::Comment(text), span) = next_spanned(iter).unwrap() else {
+                unreachable!()
+            };
+            Ok(Statement::Comment { text, trailing: false, span })
+        }
         Some(Token::True) => {
             let (_, span) = next_spanned(iter).unwrap();
             Ok(Statement::BoolLiteral(true, span))
@@ -90,8 +210,70 @@ fn parse_statement_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spa
                 Some((tok, span)) => bail!("Expected string after 'run', got {:?} at {:?}", tok, span),
                 None => bail!("Expected string after 'run'"),
             };
+
+            let mut end_span = cmd_span;
+            let cwd = if matches!(peek_token(iter), Some(Token::In)) {
+                next_spanned(iter);
+                match next_spanned(iter) {
+                    Some((Token::String(dir), span)) => {
+                        end_span = span;
+                        Some(dir)
+                    }
+                    Some((tok, span)) => bail!("Expected directory string after 'in', got {:?} at {:?}", tok, span),
+                    None => bail!("Expected directory string after 'in'"),
+                }
+            } else {
+                None
+            };
+
+            let env = if matches!(peek_token(iter), Some(Token::Env)) {
+                let (env, env_span) = parse_env_map(iter)?;
+                end_span = env_span;
+                env
+            } else {
+                Vec::new()
+            };
+
+            let fixes = if matches!(peek_token(iter), Some(Token::Identifier(id)) if id == "fixes") {
+                end_span = next_spanned(iter).unwrap().1;
+                true
+            } else {
+                false
+            };
+
+            let mut retries = 0;
+            let mut backoff = 0.0;
+            if matches!(peek_token(iter), Some(Token::Identifier(id)) if id == "retry") {
+                next_spanned(iter);
+                let (count, count_span) = match next_spanned(iter) {
+                    Some((Token::Number(n), span)) if n >= 0.0 && n.fract() == 0.0 && n <= MAX_RETRIES as f64 => (n as usize, span),
+                    Some((tok, span)) => bail!("Expected retry count between 0 and {} after 'retry', got {:?} at {:?}", MAX_RETRIES, tok, span),
+                    None => bail!("Expected retry count after 'retry'"),
+                };
+                retries = count;
+                end_span = count_span;
+
+                if matches!(peek_token(iter), Some(Token::Identifier(id)) if id == "backoff") {
+                    next_spanned(iter);
+                    let (secs, secs_span) = match next_spanned(iter) {
+                        Some((Token::Number(n), span)) if (0.0..=MAX_BACKOFF_SECS).contains(&n) => (n, span),
+                        Some((tok, span)) => bail!("Expected seconds between 0 and {} after 'backoff', got {:?} at {:?}", MAX_BACKOFF_SECS, tok, span),
+                        None => bail!("Expected seconds after 'backoff'"),
+                    };
+                    backoff = secs;
+                    end_span = secs_span;
+                }
+            }
             
-            Ok(Statement::Run(cmd, start_span.merge(&cmd_span)))
+            Ok(Statement::Run {
+                command: cmd,
+                cwd,
+                env,
+                fixes,
+                retries,
+                backoff,
+                span: start_span.merge(&end_span),
+            })
         }
         Some(Token::Block) => {
             let (_, start_span) = next_or_eof(iter, "'block' keyword")?;
@@ -117,6 +299,16 @@ fn parse_statement_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spa
             
             Ok(Statement::AllowCommand(cmd, start_span.merge(&cmd_span)))
         }
+        Some(Token::Require) => {
+            let (_, start_span) = next_spanned(iter).unwrap();
+            match next_spanned(iter) {
+                Some((Token::Identifier(what), span)) if what == "clean" => {
+                    Ok(Statement::RequireClean(start_span.merge(&span)))
+                }
+                Some((tok, span)) => bail!("Expected 'clean' after 'require', got {:?} at {:?}", tok, span),
+                None => bail!("Expected 'clean' after 'require'"),
+            }
+        }
         Some(Token::Match) => parse_match_spanned(iter),
         Some(Token::Use) => parse_use_spanned(iter),
         Some(Token::Import) => parse_import_spanned(iter),
@@ -151,9 +343,12 @@ fn parse_statement_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spa
                 parse_macro_call_spanned(iter)
             }
         }
+        Some(Token::Identifier(_)) if is_assign => {
+            parse_assign_spanned(iter)
+        }
         Some(token) => {
             if let Token::Identifier(name) = token {
-                let suggestion = suggest_keyword(&name);
+                let suggestion = suggest_keyword(name);
                 let msg = if let Some(suggested) = suggestion {
                     format!("Unknown keyword '{}'. Did you mean '{}'?", name, suggested)
                 } else {
@@ -206,11 +401,11 @@ fn levenshtein_distance(a: &str, b: &str) -> usize {
     
     let mut matrix = vec![vec![0; b_len + 1]; a_len + 1];
     
-    for i in 0..=a_len {
-        matrix[i][0] = i;
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[0] = i;
     }
-    for j in 0..=b_len {
-        matrix[0][j] = j;
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
     }
     
     for i in 1..=a_len {
@@ -225,7 +420,7 @@ fn levenshtein_distance(a: &str, b: &str) -> usize {
     matrix[a_len][b_len]
 }
 
-fn parse_match_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_match_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     skip_newlines_spanned(iter);
 
@@ -233,8 +428,10 @@ fn parse_match_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
         Some((Token::File, span)) => (MatchSubject::File(span), span),
         Some((Token::Content, span)) => (MatchSubject::Content(span), span),
         Some((Token::Diff, span)) => (MatchSubject::Diff(span), span),
-        Some((tok, span)) => bail!("Expected 'file', 'content', or 'diff' after 'match', got {:?} at {:?}", tok, span),
-        None => bail!("Expected 'file', 'content', or 'diff' after 'match'"),
+        Some((Token::BranchName, span)) => (MatchSubject::Branch(span), span),
+        Some((Token::Identifier(id), span)) if id == "branch" => (MatchSubject::Branch(span), span),
+        Some((tok, span)) => bail!("Expected 'file', 'content', 'diff', or 'branch' after 'match', got {:?} at {:?}", tok, span),
+        None => bail!("Expected 'file', 'content', 'diff', or 'branch' after 'match'"),
     };
 
     skip_newlines_spanned(iter);
@@ -275,6 +472,7 @@ fn parse_match_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
                     Some((tok, span)) => bail!("Expected string after 'matches', got {:?} at {:?}", tok, span),
                     None => bail!("Expected string after 'matches'"),
                 };
+                let (regex, regex_span) = parse_ignore_case_spanned(iter, regex, regex_span);
                 (MatchPattern::Matches(regex, kw_span.merge(&regex_span)), kw_span.merge(&regex_span))
             }
             Some(Token::Greater) => {
@@ -318,7 +516,7 @@ fn parse_match_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
     Ok(Statement::Match { subject, arms, span: full_span })
 }
 
-fn parse_use_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_use_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     skip_newlines_spanned(iter);
 
@@ -365,7 +563,7 @@ fn parse_use_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedTo
     })
 }
 
-fn parse_import_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_import_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     skip_newlines_spanned(iter);
 
@@ -398,7 +596,7 @@ fn parse_import_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanne
     })
 }
 
-fn parse_let_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_let_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
 
     let (name, _) = match next_spanned(iter) {
@@ -408,6 +606,33 @@ fn parse_let_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedTo
     };
 
     let _ = expect_token_spanned(iter, Token::Equals)?;
+    let (items, end_span) = parse_string_list_spanned(iter)?;
+
+    Ok(Statement::LetStringList { 
+        name, 
+        items, 
+        span: start_span.merge(&end_span),
+    })
+}
+
+fn parse_assign_spanned(iter: &mut TokenStream) -> Result<Statement> {
+    let (name, start_span) = match next_spanned(iter) {
+        Some((Token::Identifier(id), span)) => (id, span),
+        _ => unreachable!(),
+    };
+
+    let _ = expect_token_spanned(iter, Token::Equals)?;
+    let (items, end_span) = parse_string_list_spanned(iter)?;
+
+    Ok(Statement::Assign {
+        name,
+        items,
+        span: start_span.merge(&end_span),
+    })
+}
+
+/// Parses `["a", "b"]`, returning the items and the span of the closing bracket
+fn parse_string_list_spanned(iter: &mut TokenStream) -> Result<(Vec<String>, Span)> {
     let _ = expect_token_spanned(iter, Token::LeftBracket)?;
 
     let mut items = Vec::new();
@@ -443,15 +668,10 @@ fn parse_let_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedTo
     }
 
     let end_span = expect_token_spanned(iter, Token::RightBracket)?;
-
-    Ok(Statement::LetStringList { 
-        name, 
-        items, 
-        span: start_span.merge(&end_span),
-    })
+    Ok((items, end_span))
 }
 
-fn parse_file_rule_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_file_rule_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (pattern, start_span) = match next_spanned(iter) {
         Some((Token::String(s), span)) => (s, span),
         _ => unreachable!(),
@@ -486,27 +706,27 @@ fn parse_file_rule_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spa
     })
 }
 
-fn parse_body_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Vec<Statement>> {
+fn parse_body_spanned(iter: &mut TokenStream) -> Result<Vec<Statement>> {
     let mut statements = Vec::new();
     loop {
-        skip_newlines_spanned(iter);
+        let after_newline = skip_blank_lines_spanned(iter);
         if matches!(peek_token(iter), Some(Token::RightBrace)) || iter.peek().is_none() {
             break;
         }
-        statements.push(parse_statement_spanned(iter)?);
+        let stmt = parse_statement_spanned(iter)?;
+        push_statement(&mut statements, stmt, after_newline);
     }
     Ok(statements)
 }
 
-fn parse_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<BlockCondition> {
+fn parse_condition_spanned(iter: &mut TokenStream) -> Result<BlockCondition> {
     parse_or_condition_spanned(iter)
 }
 
-fn parse_or_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<BlockCondition> {
+fn parse_or_condition_spanned(iter: &mut TokenStream) -> Result<BlockCondition> {
     let mut left = parse_and_condition_spanned(iter)?;
     loop {
-        skip_newlines_spanned(iter);
-        if matches!(peek_token(iter), Some(Token::Or)) {
+        if skip_newlines_before_spanned(iter, &Token::Or) {
             let (_, or_span) = next_spanned(iter).unwrap();
             let right = parse_and_condition_spanned(iter)?;
             let right_span = match &right {
@@ -529,11 +749,10 @@ fn parse_or_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<
     Ok(left)
 }
 
-fn parse_and_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<BlockCondition> {
+fn parse_and_condition_spanned(iter: &mut TokenStream) -> Result<BlockCondition> {
     let mut left = parse_atom_condition_spanned(iter)?;
     loop {
-        skip_newlines_spanned(iter);
-        if matches!(peek_token(iter), Some(Token::And)) {
+        if skip_newlines_before_spanned(iter, &Token::And) {
             let (_, and_span) = next_spanned(iter).unwrap();
             let right = parse_atom_condition_spanned(iter)?;
             let right_span = match &right {
@@ -556,7 +775,7 @@ fn parse_and_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter
     Ok(left)
 }
 
-fn parse_atom_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<BlockCondition> {
+fn parse_atom_condition_spanned(iter: &mut TokenStream) -> Result<BlockCondition> {
     skip_newlines_spanned(iter);
     
     let is_negated = if matches!(peek_token(iter), Some(Token::Not)) {
@@ -633,6 +852,38 @@ fn parse_atom_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIte
             let (_, span) = next_spanned(iter).unwrap();
             Ok(BlockCondition::AuthorMissing(span))
         }
+        Some(Token::IsBinary) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::IsBinary(span))
+        }
+        Some(Token::AnyBinary) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::AnyBinary(span))
+        }
+        Some(Token::MergeInProgress) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::MergeInProgress(span))
+        }
+        Some(Token::HasConflicts) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::HasConflicts(span))
+        }
+        Some(Token::WorktreeDirty) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::WorktreeDirty(span))
+        }
+        Some(Token::EmptyCommit) => {
+            let (_, span) = next_spanned(iter).unwrap();
+            Ok(BlockCondition::EmptyCommit(span))
+        }
+        Some(Token::TagExists) => {
+            let (_, start_span) = next_spanned(iter).unwrap();
+            match next_spanned(iter) {
+                Some((Token::String(name), span)) => Ok(BlockCondition::TagExists(name, start_span.merge(&span))),
+                Some((tok, span)) => bail!("Expected tag name string after 'tag_exists', got {:?} at {:?}", tok, span),
+                None => bail!("Expected tag name string after 'tag_exists'"),
+            }
+        }
         Some(Token::Env) => {
             let (_, start_span) = next_spanned(iter).unwrap();
             let (key, _) = match next_spanned(iter) {
@@ -640,13 +891,22 @@ fn parse_atom_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIte
                 Some((tok, span)) => bail!("Expected environment variable name, got {:?} at {:?}", tok, span),
                 None => bail!("Expected environment variable name"),
             };
-            let _ = expect_token_spanned(iter, Token::Equals)?;
+            let op = match next_spanned(iter) {
+                Some((tok @ (Token::Equals | Token::NotEquals | Token::Contains | Token::Contain), _)) => tok,
+                Some((tok, span)) => bail!("Expected '=', '!=' or 'contains' after environment variable name, got {:?} at {:?}", tok, span),
+                None => bail!("Expected '=', '!=' or 'contains' after environment variable name"),
+            };
             let (value, end_span) = match next_spanned(iter) {
                 Some((Token::String(s), span)) => (s, span),
-                Some((tok, span)) => bail!("Expected value after '=', got {:?} at {:?}", tok, span),
-                None => bail!("Expected value after '='"),
+                Some((tok, span)) => bail!("Expected string value for env condition, got {:?} at {:?}", tok, span),
+                None => bail!("Expected string value for env condition"),
             };
-            Ok(BlockCondition::EnvEquals(key, value, start_span.merge(&end_span)))
+            let span = start_span.merge(&end_span);
+            Ok(match op {
+                Token::Equals => BlockCondition::EnvEquals(key, value, span),
+                Token::NotEquals => BlockCondition::EnvNotEquals(key, value, span),
+                _ => BlockCondition::EnvContains(key, value, span),
+            })
         }
         _ => Err(anyhow::anyhow!("Expected condition, got {:?}", peek_token(iter))),
     };
@@ -661,6 +921,8 @@ fn parse_atom_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIte
             BlockCondition::AuthorEmailSet(span) => *span,
             BlockCondition::AuthorMissing(span) => *span,
             BlockCondition::EnvEquals(_, _, span) => *span,
+            BlockCondition::EnvNotEquals(_, _, span) => *span,
+            BlockCondition::EnvContains(_, _, span) => *span,
             _ => result_cond.span(),
         };
         Ok(BlockCondition::Not { inner: Box::new(result_cond), span })
@@ -669,7 +931,7 @@ fn parse_atom_condition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIte
     }
 }
 
-fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Option<BlockCondition>> {
+fn try_parse_unified_comparison_spanned(iter: &mut TokenStream) -> Result<Option<BlockCondition>> {
     let start_pos = iter.clone();
     
     let (property_token, property_span) = match next_spanned(iter) {
@@ -682,14 +944,18 @@ fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec:
         Token::BranchName => PropertyValue::BranchName(property_span),
         Token::CommitMessage => PropertyValue::CommitMessage(property_span),
         Token::AuthorEmail => PropertyValue::Placeholder("author_email".to_string(), property_span),
-        Token::ModifiedLines => PropertyValue::ModifiedLines(property_span),
-        Token::FilesChanged => PropertyValue::FilesChanged(property_span),
-        Token::Additions => PropertyValue::Additions(property_span),
-        Token::Deletions => PropertyValue::Deletions(property_span),
+        Token::ModifiedLines => PropertyValue::ModifiedLines(parse_stat_pattern(iter), property_span),
+        Token::FilesChanged => PropertyValue::FilesChanged(parse_stat_pattern(iter), property_span),
+        Token::StagedCount => PropertyValue::StagedCount(property_span),
+        Token::Additions => PropertyValue::Additions(parse_stat_pattern(iter), property_span),
+        Token::Deletions => PropertyValue::Deletions(parse_stat_pattern(iter), property_span),
         Token::CommitsAhead => PropertyValue::CommitsAhead(property_span),
+        Token::SubjectLength => PropertyValue::SubjectLength(property_span),
+        Token::MaxLineLength => PropertyValue::MaxLineLength(property_span),
         Token::FileExists => PropertyValue::Placeholder("file_exists".to_string(), property_span),
         Token::FileSize => PropertyValue::FileSize(property_span),
         Token::Diff => PropertyValue::Diff(property_span),
+        Token::AddedLines => PropertyValue::AddedLines(property_span),
         _ => {
             *iter = start_pos;
             return Ok(None);
@@ -717,6 +983,7 @@ fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec:
                     return Ok(None);
                 }
             };
+            let (pattern, pattern_span) = parse_ignore_case_spanned(iter, pattern, pattern_span);
             (ComparisonOperator::Matches, ComparisonValue::String(pattern, pattern_span), pattern_span)
         }
         
@@ -728,6 +995,7 @@ fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec:
                     return Ok(None);
                 }
             };
+            let (regex, regex_span) = parse_ignore_case_spanned(iter, regex, regex_span);
             (ComparisonOperator::Matches, ComparisonValue::String(regex, regex_span), regex_span)
         }
         
@@ -788,7 +1056,7 @@ fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec:
             (ComparisonOperator::Equals, value, value_span)
         }
         
-        Token::Contain => {
+        Token::Contain | Token::Contains => {
             let (text, text_span) = match next_spanned(iter) {
                 Some((Token::String(s), span)) => (s, span),
                 _ => {
@@ -816,7 +1084,34 @@ fn try_parse_unified_comparison_spanned(iter: &mut std::iter::Peekable<std::vec:
     }))
 }
 
-fn parse_when_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+/// Consume an optional `matching "<glob>"` that scopes a diff statistic to some files
+fn parse_stat_pattern(iter: &mut TokenStream) -> Option<String> {
+    if !matches!(peek_token(iter), Some(Token::Matching)) {
+        return None;
+    }
+
+    let checkpoint = iter.clone();
+    next_spanned(iter);
+    match next_spanned(iter) {
+        Some((Token::String(pattern), _)) => Some(pattern),
+        _ => {
+            *iter = checkpoint;
+            None
+        }
+    }
+}
+
+/// Consume an optional `ignorecase` after a regex, which turns on the `(?i)` flag
+fn parse_ignore_case_spanned(iter: &mut TokenStream, regex: String, regex_span: Span) -> (String, Span) {
+    if !matches!(peek_token(iter), Some(Token::IgnoreCase)) {
+        return (regex, regex_span);
+    }
+
+    let (_, flag_span) = next_spanned(iter).unwrap();
+    (format!("(?i){}", regex), regex_span.merge(&flag_span))
+}
+
+fn parse_when_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     let condition = parse_condition_spanned(iter)?;
 
@@ -850,7 +1145,7 @@ fn parse_when_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedT
     })
 }
 
-fn parse_foreach_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_foreach_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     
     let (var, _) = match next_spanned(iter) {
@@ -945,8 +1240,19 @@ fn parse_foreach_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spann
             })
         }
 
-        Some(Token::StagedFiles) => {
-            next_spanned(iter);
+        Some(Token::StagedFiles | Token::AddedFiles | Token::DeletedFiles | Token::ChangedSince | Token::CommitFiles | Token::ConflictFiles) => {
+            let source = match next_spanned(iter) {
+                Some((Token::AddedFiles, _)) => FileSource::Added,
+                Some((Token::DeletedFiles, _)) => FileSource::Deleted,
+                Some((Token::CommitFiles, _)) => FileSource::CommitFiles,
+                Some((Token::ConflictFiles, _)) => FileSource::ConflictFiles,
+                Some((Token::ChangedSince, _)) => match next_spanned(iter) {
+                    Some((Token::String(git_ref), _)) => FileSource::ChangedSince(git_ref),
+                    Some((tok, span)) => bail!("Expected git ref string after 'changed_since', got {:?} at {:?}", tok, span),
+                    None => bail!("Expected git ref string after 'changed_since'"),
+                },
+                _ => FileSource::Staged,
+            };
 
             let pattern = if matches!(peek_token(iter), Some(Token::Matching)) {
                 next_spanned(iter);
@@ -992,6 +1298,7 @@ fn parse_foreach_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spann
 
             Ok(Statement::ForEachStagedFiles { 
                 var, 
+                source,
                 pattern, 
                 where_cond, 
                 body, 
@@ -999,13 +1306,25 @@ fn parse_foreach_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spann
             })
         }
 
-        Some(tok) => bail!("Expected '{{' or 'staged_files' after 'foreach <var> in', got {:?}", tok),
-        None => bail!("Expected '{{' or 'staged_files' after 'foreach <var> in'"),
+        Some(tok) => bail!("Expected '{{', '[' or a file collection (staged_files, added_files, deleted_files, changed_since \"<ref>\", commit_files, conflict_files) after 'foreach <var> in', got {:?}", tok),
+        None => bail!("Expected '{{', '[' or a file collection after 'foreach <var> in'"),
     }
 }
 
-fn parse_parallel_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_parallel_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
+
+    let pattern = if matches!(peek_token(iter), Some(Token::Matching)) {
+        next_spanned(iter);
+        match next_spanned(iter) {
+            Some((Token::String(s), _)) => Some(s),
+            Some((tok, span)) => bail!("Expected pattern string after 'matching', got {:?} at {:?}", tok, span),
+            None => bail!("Expected pattern string after 'matching'"),
+        }
+    } else {
+        None
+    };
+
     let _ = expect_token_spanned(iter, Token::LeftBrace)?;
     let mut commands = Vec::new();
 
@@ -1045,11 +1364,12 @@ fn parse_parallel_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Span
     
     Ok(Statement::Parallel { 
         commands, 
+        pattern,
         span: start_span.merge(&end_span),
     })
 }
 
-fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_group_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     
     let (name, _) = match next_spanned(iter) {
@@ -1060,14 +1380,13 @@ fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
 
     skip_newlines_spanned(iter);
     let _ = expect_token_spanned(iter, Token::LeftBrace)?;
-    skip_newlines_spanned(iter);
 
     let mut severity: Option<GroupSeverity> = None;
     let mut enabled: Option<bool> = None;
     let mut body: Vec<Statement> = Vec::new();
 
     loop {
-        skip_newlines_spanned(iter);
+        let after_newline = skip_blank_lines_spanned(iter);
         
         if matches!(peek_token(iter), Some(Token::RightBrace)) {
             break;
@@ -1088,7 +1407,6 @@ fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
                     None => bail!("Expected severity value"),
                 };
                 severity = Some(sev);
-                skip_newlines_spanned(iter);
             }
             
             Some(Token::Enabled) => {
@@ -1104,7 +1422,6 @@ fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
                     None => bail!("Expected boolean value after 'enabled:'"),
                 };
                 enabled = Some(en);
-                skip_newlines_spanned(iter);
             }
             
             Some(Token::RightBrace) => {
@@ -1112,7 +1429,8 @@ fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
             }
             
             _ => {
-                body.push(parse_statement_spanned(iter)?);
+                let stmt = parse_statement_spanned(iter)?;
+                push_statement(&mut body, stmt, after_newline);
             }
         }
     }
@@ -1132,7 +1450,7 @@ fn parse_group_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Spanned
     })
 }
 
-fn parse_macro_definition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_macro_definition_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (first_token, start_span) = next_spanned(iter).unwrap();
     skip_newlines_spanned(iter);
 
@@ -1208,7 +1526,7 @@ fn parse_macro_definition_spanned(iter: &mut std::iter::Peekable<std::vec::IntoI
     })
 }
 
-fn parse_macro_call_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>) -> Result<Statement> {
+fn parse_macro_call_spanned(iter: &mut TokenStream) -> Result<Statement> {
     let (macro_name_token, start_span) = next_spanned(iter).unwrap();
     
     let macro_name = match macro_name_token {
@@ -1361,14 +1679,13 @@ fn parse_macro_call_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<Sp
     })
 }
 
-fn parse_conditional_rule_spanned(iter: &mut std::iter::Peekable<std::vec::IntoIter<SpannedToken>>, is_block: bool) -> Result<Statement> {
+fn parse_conditional_rule_spanned(iter: &mut TokenStream, is_block: bool) -> Result<Statement> {
     let (_, start_span) = next_spanned(iter).unwrap();
     skip_newlines_spanned(iter);
 
     let condition = parse_condition_spanned(iter)?;
-    skip_newlines_spanned(iter);
 
-    let message = if matches!(peek_token(iter), Some(Token::Message)) {
+    let message = if skip_newlines_before_spanned(iter, &Token::Message) {
         next_spanned(iter);
         skip_newlines_spanned(iter);
         
@@ -1381,9 +1698,7 @@ fn parse_conditional_rule_spanned(iter: &mut std::iter::Peekable<std::vec::IntoI
         None
     };
 
-    skip_newlines_spanned(iter);
-
-    let interactive = if matches!(peek_token(iter), Some(Token::Interactive)) {
+    let interactive = if skip_newlines_before_spanned(iter, &Token::Interactive) {
         next_spanned(iter);
         skip_newlines_spanned(iter);
         
@@ -1410,3 +1725,76 @@ fn parse_conditional_rule_spanned(iter: &mut std::iter::Peekable<std::vec::IntoI
         span: start_span,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize_with_spans;
+
+    fn parse(source: &str) -> Vec<Statement> {
+        parse_spanned(tokenize_with_spans(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_let_and_reassignment() {
+        let ast = parse("let exts = [\"rs\"]\nexts = [\"rs\", \"toml\"]\n");
+        assert!(matches!(&ast[0], Statement::LetStringList { name, items, .. } if name == "exts" && items == &["rs"]));
+        assert!(matches!(&ast[1], Statement::Assign { name, items, .. } if name == "exts" && items == &["rs", "toml"]));
+    }
+
+    #[test]
+    fn unknown_identifier_is_not_an_assignment() {
+        let err = parse_spanned(tokenize_with_spans("exts [\"rs\"]\n").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Unknown keyword 'exts'"));
+    }
+
+    #[test]
+    fn comments_stay_with_their_statements() {
+        let ast = parse("# leading\nrun \"a\" # after a\nblock_if true\n  and false\n# before b\nrun \"b\"\n");
+
+        assert!(matches!(&ast[0], Statement::Comment { text, trailing: false, .. } if text == "# leading"));
+        assert!(matches!(&ast[1], Statement::Run { command, .. } if command == "a"));
+        assert!(matches!(&ast[2], Statement::Comment { text, trailing: true, .. } if text == "# after a"));
+        assert!(matches!(&ast[3], Statement::ConditionalRule { .. }));
+        assert!(matches!(&ast[4], Statement::Comment { text, trailing: false, .. } if text == "# before b"));
+        assert!(matches!(&ast[5], Statement::Run { command, .. } if command == "b"));
+        assert_eq!(ast.len(), 6);
+    }
+
+    #[test]
+    fn parses_bounded_retry_and_backoff() {
+        let ast = parse("run \"cargo test\" retry 3 backoff 0.5\n");
+        assert!(matches!(&ast[0], Statement::Run { retries: 3, backoff, .. } if *backoff == 0.5));
+
+        for source in ["run \"a\" retry 11\n", "run \"a\" retry 99999999999999999999999\n", "run \"a\" retry 2 backoff 99999999999999999999999\n", "run \"a\" retry 1.5\n"] {
+            let err = parse_spanned(tokenize_with_spans(source).unwrap()).unwrap_err();
+            assert!(err.to_string().contains("between 0 and"), "{}: {}", source, err);
+        }
+    }
+
+    #[test]
+    fn ignorecase_prefixes_the_regex_flag() {
+        let ast = parse("block_if commit_message matches \"^wip\" ignorecase\nmatch file {\n  matches \"\\.md$\" ignorecase -> run \"a\"\n}\n");
+        assert!(matches!(
+            &ast[0],
+            Statement::ConditionalRule { condition: BlockCondition::Comparison { right: ComparisonValue::String(regex, _), .. }, .. } if regex == "(?i)^wip"
+        ));
+        assert!(matches!(
+            &ast[1],
+            Statement::Match { arms, .. } if matches!(&arms[0].pattern, MatchPattern::Matches(regex, _) if regex == "(?i)\\.md$")
+        ));
+    }
+
+    #[test]
+    fn parses_parallel_matching_clause() {
+        let ast = parse("parallel matching \"*.rs\" {\n  run \"cargo fmt --check\"\n  \"cargo clippy\"\n}\nparallel {\n  run \"a\"\n}\n");
+        assert!(matches!(
+            &ast[0],
+            Statement::Parallel { commands, pattern: Some(pattern), .. } if pattern == "*.rs" && commands == &["cargo fmt --check", "cargo clippy"]
+        ));
+        assert!(matches!(&ast[1], Statement::Parallel { pattern: None, .. }));
+
+        let err = parse_spanned(tokenize_with_spans("parallel matching { run \"a\" }\n").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Expected pattern string after 'matching'"));
+    }
+}
@@ -1,5 +1,8 @@
-pub use githook_syntax::{Statement, Token, tokenize_with_spans, parse_spanned, Diagnostic};
-pub use githook_eval::{execute, execute_with_filters, ExecutionStatus};
+pub use githook_syntax::{Statement, Token, SpannedToken, Span, tokenize_with_spans, parse_spanned, format, Diagnostic, explain};
+pub use githook_syntax::error::MISSING_CONFIG_CODE;
+pub use githook_eval::{execute, execute_with_filters, execute_with_context, BlockLevel, CheckResult, Executor, ExecutionContext, ExecutionStatus, RunReport};
+pub use githook_eval::{Reporter, HumanReporter, JsonReporter};
+pub use githook_eval::package_resolver;
 pub use githook_git;
 
 pub mod prelude {